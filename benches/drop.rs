@@ -0,0 +1,73 @@
+//! Times dropping large vectors, comparing the bulk `drop_in_place` path against popping the
+//! elements off one at a time, which is what dropping used to do. For `Box` the time goes to
+//! `free`, and popping frees in reverse allocation order, which the system allocator tends to
+//! like better than the front-to-back order `Vec` drops in too.
+//!
+//! ```
+//! cargo bench --bench drop
+//! ```
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use my_vec::MyVec;
+
+const LEN: usize = 10_000_000;
+const ROUNDS: usize = 5;
+
+/// Runs `setup` and then `drop` `ROUNDS` times, and prints the best time spent in `drop`.
+fn bench<S, D>(name: &str, mut setup: impl FnMut() -> S, mut drop: D)
+where
+    D: FnMut(S),
+{
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let input = black_box(setup());
+        let start = Instant::now();
+        drop(input);
+        best = best.min(start.elapsed());
+    }
+    println!("{:<40} {:>10.3} ms", name, best.as_secs_f64() * 1e3);
+}
+
+fn pop_all<T>(mut v: MyVec<T>) {
+    while let Some(x) = v.pop() {
+        black_box(x);
+    }
+}
+
+fn filled<T>(make: impl Fn(usize) -> T) -> impl FnMut() -> MyVec<T> {
+    move || (0..LEN).map(&make).collect()
+}
+
+fn main() {
+    println!("dropping {} elements, best of {}", LEN, ROUNDS);
+
+    bench("u64: pop loop", filled(|i| i as u64), pop_all);
+    bench("u64: drop", filled(|i| i as u64), drop);
+    bench("u64: drop IntoIter", filled(|i| i as u64), |v| {
+        drop(v.into_iter())
+    });
+    bench("u64: drop Drain", filled(|i| i as u64), |mut v| {
+        drop(v.drain(1..));
+    });
+
+    bench(
+        "Box<u64>: pop loop",
+        filled(|i| Box::new(i as u64)),
+        pop_all,
+    );
+    bench("Box<u64>: drop", filled(|i| Box::new(i as u64)), drop);
+    bench(
+        "Box<u64>: drop IntoIter",
+        filled(|i| Box::new(i as u64)),
+        |v| drop(v.into_iter()),
+    );
+    bench(
+        "Box<u64>: drop Drain",
+        filled(|i| Box::new(i as u64)),
+        |mut v| {
+            drop(v.drain(1..));
+        },
+    );
+}
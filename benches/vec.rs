@@ -0,0 +1,314 @@
+//! Runs the same workloads on `MyVec` and `std::vec::Vec` and prints the timings side by side,
+//! for a few element sizes. There are no dependencies, so it runs offline:
+//!
+//! ```
+//! cargo bench --bench vec
+//! cargo bench --bench vec -- drain      # only the cases whose name contains "drain"
+//! ```
+//!
+//! Each case reports the best of `ROUNDS` runs; the last column is `MyVec` time over `Vec` time.
+
+use std::hint::black_box;
+use std::iter::FromIterator;
+use std::time::{Duration, Instant};
+
+use my_vec::MyVec;
+
+const ROUNDS: usize = 7;
+/// Elements per case for the linear workloads.
+const LEN: usize = 100_000;
+/// Elements per case for the workloads that shift the whole vector each step.
+const SHIFT_LEN: usize = 5_000;
+
+/// The part of the vector API the workloads use, so each one is written once for both types.
+trait VecLike<T>: Clone + FromIterator<T> + Extend<T> {
+    fn new() -> Self;
+    fn with_capacity(capacity: usize) -> Self;
+    fn len(&self) -> usize;
+    fn push(&mut self, value: T);
+    fn pop(&mut self) -> Option<T>;
+    fn insert(&mut self, index: usize, value: T);
+    fn remove(&mut self, index: usize) -> T;
+    fn as_slice(&self) -> &[T];
+    fn drain_all(&mut self, start: usize) -> usize;
+}
+
+macro_rules! impl_vec_like {
+    ($V:ident) => {
+        impl<T: Clone> VecLike<T> for $V<T> {
+            fn new() -> Self {
+                $V::new()
+            }
+            fn with_capacity(capacity: usize) -> Self {
+                $V::with_capacity(capacity)
+            }
+            fn len(&self) -> usize {
+                <[T]>::len(self)
+            }
+            fn push(&mut self, value: T) {
+                $V::push(self, value)
+            }
+            fn pop(&mut self) -> Option<T> {
+                $V::pop(self)
+            }
+            fn insert(&mut self, index: usize, value: T) {
+                $V::insert(self, index, value)
+            }
+            fn remove(&mut self, index: usize) -> T {
+                $V::remove(self, index)
+            }
+            fn as_slice(&self) -> &[T] {
+                self
+            }
+            fn drain_all(&mut self, start: usize) -> usize {
+                $V::drain(self, start..).map(black_box).count()
+            }
+        }
+    };
+}
+
+impl_vec_like!(MyVec);
+impl_vec_like!(Vec);
+
+/// An element type of a given size that's cheap to make from an index.
+trait Elem: Clone {
+    const NAME: &'static str;
+    fn make(i: usize) -> Self;
+    fn key(&self) -> u64;
+}
+
+impl Elem for u8 {
+    const NAME: &'static str = "u8";
+    fn make(i: usize) -> Self {
+        i as u8
+    }
+    fn key(&self) -> u64 {
+        *self as u64
+    }
+}
+
+impl Elem for u64 {
+    const NAME: &'static str = "u64";
+    fn make(i: usize) -> Self {
+        i as u64
+    }
+    fn key(&self) -> u64 {
+        *self
+    }
+}
+
+impl Elem for [u64; 16] {
+    const NAME: &'static str = "[u64; 16]";
+    fn make(i: usize) -> Self {
+        [i as u64; 16]
+    }
+    fn key(&self) -> u64 {
+        self[0]
+    }
+}
+
+impl Elem for String {
+    const NAME: &'static str = "String";
+    fn make(i: usize) -> Self {
+        i.to_string()
+    }
+    fn key(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+/// Times `run` on fresh input from `setup` and returns the best of `ROUNDS`. Setting up and
+/// dropping whatever `run` returns aren't timed.
+fn time<S, R>(mut setup: impl FnMut() -> S, mut run: impl FnMut(S) -> R) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let input = black_box(setup());
+        let start = Instant::now();
+        let output = black_box(run(input));
+        best = best.min(start.elapsed());
+        drop(output);
+    }
+    best
+}
+
+fn filled<T: Elem, V: VecLike<T>>(len: usize) -> V {
+    (0..len).map(T::make).collect()
+}
+
+/// Every workload, generic over the vector type so both get the exact same code.
+fn workloads<T: Elem, V: VecLike<T>>(cases: &mut Cases<'_>) {
+    cases.run(
+        "push",
+        || (),
+        |()| {
+            let mut v = V::new();
+            for i in 0..LEN {
+                v.push(T::make(i));
+            }
+            v
+        },
+    );
+    cases.run(
+        "push preallocated",
+        || V::with_capacity(LEN),
+        |mut v| {
+            for i in 0..LEN {
+                v.push(T::make(i));
+            }
+            v
+        },
+    );
+    cases.run(
+        "insert front",
+        || V::with_capacity(SHIFT_LEN),
+        |mut v| {
+            for i in 0..SHIFT_LEN {
+                v.insert(0, T::make(i));
+            }
+            v
+        },
+    );
+    cases.run(
+        "insert middle",
+        || V::with_capacity(SHIFT_LEN),
+        |mut v| {
+            for i in 0..SHIFT_LEN {
+                let mid = v.len() / 2;
+                v.insert(mid, T::make(i));
+            }
+            v
+        },
+    );
+    cases.run(
+        "insert back",
+        || V::with_capacity(LEN),
+        |mut v| {
+            for i in 0..LEN {
+                let len = v.len();
+                v.insert(len, T::make(i));
+            }
+            v
+        },
+    );
+    cases.run(
+        "remove front",
+        || filled::<T, V>(SHIFT_LEN),
+        |mut v| {
+            while v.len() > 0 {
+                black_box(v.remove(0));
+            }
+            v
+        },
+    );
+    cases.run(
+        "remove middle",
+        || filled::<T, V>(SHIFT_LEN),
+        |mut v| {
+            while v.len() > 0 {
+                black_box(v.remove(v.len() / 2));
+            }
+            v
+        },
+    );
+    cases.run(
+        "remove back",
+        || filled::<T, V>(LEN),
+        |mut v| {
+            while let Some(x) = v.pop() {
+                black_box(x);
+            }
+            v
+        },
+    );
+    cases.run(
+        "iterate",
+        || filled::<T, V>(LEN),
+        |v| {
+            let sum = v
+                .as_slice()
+                .iter()
+                .fold(0u64, |acc, x| acc.wrapping_add(x.key()));
+            (v, sum)
+        },
+    );
+    cases.run(
+        "drain",
+        || filled::<T, V>(LEN),
+        |mut v| {
+            let n = v.drain_all(LEN / 2);
+            (v, n)
+        },
+    );
+    cases.run(
+        "extend",
+        || (V::new(), filled::<T, Vec<T>>(LEN)),
+        |(mut v, items)| {
+            v.extend(items);
+            v
+        },
+    );
+    cases.run(
+        "extend from filter",
+        || V::new(),
+        |mut v| {
+            v.extend((0..LEN).filter(|i| i % 3 != 0).map(T::make));
+            v
+        },
+    );
+    cases.run("clone", || filled::<T, V>(LEN), |v| (v.clone(), v));
+    cases.run("drop", || filled::<T, V>(LEN), drop);
+}
+
+/// Times the cases whose label the filter lets through, skipping the rest without running them.
+struct Cases<'f> {
+    elem: &'static str,
+    filter: Option<&'f str>,
+    timings: Vec<(String, Duration)>,
+}
+
+impl<'f> Cases<'f> {
+    fn new(elem: &'static str, filter: Option<&'f str>) -> Self {
+        Cases {
+            elem,
+            filter,
+            timings: Vec::new(),
+        }
+    }
+
+    fn run<S, R>(&mut self, name: &str, setup: impl FnMut() -> S, run: impl FnMut(S) -> R) {
+        let label = format!("{} {}", self.elem, name);
+        if self.filter.is_none_or(|filter| label.contains(filter)) {
+            self.timings.push((label, time(setup, run)));
+        }
+    }
+}
+
+fn compare<T: Elem>(filter: Option<&str>) {
+    let mut mine = Cases::new(T::NAME, filter);
+    workloads::<T, MyVec<T>>(&mut mine);
+    let mut std = Cases::new(T::NAME, filter);
+    workloads::<T, Vec<T>>(&mut std);
+    for ((label, mine), (_, std)) in mine.timings.into_iter().zip(std.timings) {
+        println!(
+            "{:<32} {:>12.1?} {:>12.1?} {:>8.2}",
+            label,
+            mine,
+            std,
+            mine.as_secs_f64() / std.as_secs_f64()
+        );
+    }
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`; the first other argument filters cases by name
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    println!(
+        "{:<32} {:>12} {:>12} {:>8}",
+        "case", "MyVec", "Vec", "ratio"
+    );
+    let filter = filter.as_deref();
+    compare::<u8>(filter);
+    compare::<u64>(filter);
+    compare::<[u64; 16]>(filter);
+    compare::<String>(filter);
+}
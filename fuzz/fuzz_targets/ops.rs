@@ -0,0 +1,13 @@
+//! Decodes the input into operations and checks `MyVec` against `Vec` step by step, with plain,
+//! zero-sized and drop-counting elements.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+fuzz_target!(|data: &[u8]| {
+    common::run_all(&common::decode_ops(data));
+});
@@ -0,0 +1,35 @@
+//! Decodes the input into operations on a `MyVec` whose elements panic in `Drop` and `Clone`,
+//! checking that no element is dropped twice or lost without a panic.
+
+#![no_main]
+
+use std::sync::Once;
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+static SILENCE: Once = Once::new();
+
+/// Whether a panic is one of the elements going off on purpose.
+fn is_bomb(info: &std::panic::PanicHookInfo<'_>) -> bool {
+    let payload = info.payload();
+    let msg = payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&str>().copied());
+    msg.is_some_and(|msg| msg.contains(" of bomb "))
+}
+
+fuzz_target!(|data: &[u8]| {
+    SILENCE.call_once(|| {
+        let default = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !is_bomb(info) {
+                default(info)
+            }
+        }));
+    });
+    common::panicky::run_panicky(&common::decode_ops(data));
+});
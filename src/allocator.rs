@@ -0,0 +1,172 @@
+use alloc::alloc::{alloc, dealloc, realloc, Layout};
+use core::fmt;
+use core::ptr::{self, NonNull};
+
+/// The error returned by an [`Allocator`] that can't serve a request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
+/// A stable stand-in for `std::alloc::Allocator`, used to back `MyVec` with memory that doesn't
+/// come from the global allocator (arenas, bump allocators, pools, ...).
+///
+/// # Safety
+///
+/// A block returned by `allocate`, `grow` or `shrink` must stay valid, and must not be handed out
+/// again, until it's passed to `deallocate`, `grow` or `shrink` on the same allocator (or a
+/// reference to it). Moving the allocator must not invalidate its blocks.
+pub unsafe trait Allocator {
+    /// Allocates a block fitting `layout`. Zero-sized requests may return a dangling pointer.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// Releases the block at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block currently allocated by this allocator, and `layout` the layout it
+    /// was allocated with.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Extends the block at `ptr` to `new_layout`, keeping its first `old_layout.size()` bytes.
+    /// The old block is left untouched on failure.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block currently allocated by this allocator with `old_layout`, and
+    /// `new_layout.size()` must not be smaller than `old_layout.size()`.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+        move_to_new_block(self, ptr, old_layout, new_layout, old_layout.size())
+    }
+
+    /// Shrinks the block at `ptr` to `new_layout`, keeping its first `new_layout.size()` bytes.
+    /// The old block is left untouched on failure.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block currently allocated by this allocator with `old_layout`, and
+    /// `new_layout.size()` must not be larger than `old_layout.size()`.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+        move_to_new_block(self, ptr, old_layout, new_layout, new_layout.size())
+    }
+}
+
+/// Reallocation by hand: allocate a new block, copy `keep` bytes over and free the old one.
+unsafe fn move_to_new_block<A: Allocator + ?Sized>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+    keep: usize,
+) -> Result<NonNull<u8>, AllocError> {
+    let new_ptr = alloc.allocate(new_layout)?;
+    ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), keep);
+    alloc.deallocate(ptr, old_layout);
+    Ok(new_ptr)
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).shrink(ptr, old_layout, new_layout)
+    }
+}
+
+/// The global memory allocator, i.e. whatever `#[global_allocator]` is registered.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Global;
+
+/// A well-aligned, non-null pointer standing in for zero-sized blocks.
+fn dangling(layout: Layout) -> NonNull<u8> {
+    // built without an integer-to-pointer cast, so it stays usable under strict provenance
+    unsafe { NonNull::new_unchecked(ptr::null_mut::<u8>().wrapping_add(layout.align())) }
+}
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        NonNull::new(unsafe { alloc(layout) }).ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            dealloc(ptr.as_ptr(), layout)
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+        if old_layout.size() == 0 {
+            return self.allocate(new_layout);
+        }
+        // realloc keeps the old alignment, so it can only be used when that doesn't change
+        if old_layout.align() != new_layout.align() {
+            return move_to_new_block(self, ptr, old_layout, new_layout, old_layout.size());
+        }
+        NonNull::new(realloc(ptr.as_ptr(), old_layout, new_layout.size())).ok_or(AllocError)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+        if new_layout.size() == 0 {
+            self.deallocate(ptr, old_layout);
+            return Ok(dangling(new_layout));
+        }
+        if old_layout.align() != new_layout.align() {
+            return move_to_new_block(self, ptr, old_layout, new_layout, new_layout.size());
+        }
+        NonNull::new(realloc(ptr.as_ptr(), old_layout, new_layout.size())).ok_or(AllocError)
+    }
+}
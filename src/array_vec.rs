@@ -0,0 +1,363 @@
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut, Range, RangeBounds};
+use core::{ptr, slice};
+
+use crate::inline::{self, InlineStorage};
+use crate::{drop_elements, slice_range};
+
+/// The error returned when an `ArrayMyVec` is full, handing back the element that didn't fit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    pub const fn new(element: T) -> Self {
+        CapacityError { element }
+    }
+
+    /// Returns the element that couldn't be added.
+    pub fn element(self) -> T {
+        self.element
+    }
+}
+
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CapacityError: insufficient capacity")
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for CapacityError<T> {}
+
+/// A vector with a fixed capacity of `N` elements, stored inline. It never allocates.
+pub struct ArrayMyVec<T, const N: usize> {
+    len: usize,
+    data: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> ArrayMyVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            // an array of MaybeUninit doesn't need initializing
+            data: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr() as *const T, self.len) }
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        self.data.as_mut_ptr() as *mut T
+    }
+
+    /// Appends `ele`, or hands it back inside the error if the vector is full.
+    pub fn push(&mut self, ele: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError::new(ele));
+        }
+        unsafe {
+            ptr::write(self.ptr_mut().add(self.len), ele);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(ptr::read(self.ptr_mut().add(self.len))) }
+        }
+    }
+
+    /// Inserts `ele` at `idx`, or hands it back inside the error if the vector is full. Panics if
+    /// `idx > len`.
+    pub fn insert(&mut self, idx: usize, ele: T) -> Result<(), CapacityError<T>> {
+        assert!(idx <= self.len, "index out of bounds");
+        if self.len == N {
+            return Err(CapacityError::new(ele));
+        }
+        unsafe {
+            let ptr = self.ptr_mut().add(idx);
+            ptr::copy(ptr, ptr.add(1), self.len - idx);
+            ptr::write(ptr, ele);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, idx: usize) -> T {
+        assert!(idx < self.len, "index out of bounds");
+        unsafe {
+            let ptr = self.ptr_mut().add(idx);
+            let item = ptr.read();
+            ptr::copy(ptr.add(1), ptr, self.len - idx - 1);
+            self.len -= 1;
+            item
+        }
+    }
+
+    /// Removes the elements in `range`, yielding them from the returned iterator. Behaves
+    /// exactly like `MyVec::drain`.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        let Range { start, end } = slice_range(range, self.len);
+        let base = self.ptr_mut();
+        unsafe { Drain::new(base, &mut self.len, start, end) }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayMyVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayMyVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayMyVec<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.ptr_mut(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayMyVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { drop_elements(self.ptr_mut(), self.len) }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayMyVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayMyVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter::new(self)
+    }
+}
+
+impl<T, const N: usize> InlineStorage<T> for ArrayMyVec<T, N> {
+    fn ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        ArrayMyVec::ptr_mut(self)
+    }
+
+    fn take_len(&mut self) -> usize {
+        mem::replace(&mut self.len, 0)
+    }
+
+    fn from_cloned(items: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let mut v = ArrayMyVec::new();
+        for item in items {
+            if v.push(item.clone()).is_err() {
+                unreachable!("more than N items");
+            }
+        }
+        v
+    }
+}
+
+/// The iterator returned by `ArrayMyVec::drain`.
+pub type Drain<'a, T, const N: usize> = inline::Drain<'a, T, ArrayMyVec<T, N>>;
+
+/// The owning iterator of a `ArrayMyVec`.
+pub type IntoIter<T, const N: usize> = inline::IntoIter<T, ArrayMyVec<T, N>>;
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayMyVec;
+    use std::rc::Rc;
+    use std::string::{String, ToString};
+
+    static EMPTY: ArrayMyVec<u8, 4> = ArrayMyVec::new();
+
+    #[test]
+    fn const_construction() {
+        assert_eq!(EMPTY.len(), 0);
+        assert_eq!(EMPTY.capacity(), 4);
+        assert!(EMPTY.as_slice().is_empty());
+    }
+
+    #[test]
+    fn push_until_full() {
+        let mut v: ArrayMyVec<String, 2> = ArrayMyVec::new();
+        assert!(v.push("a".to_string()).is_ok());
+        assert!(v.push("b".to_string()).is_ok());
+        assert!(v.is_full());
+        let err = v.push("c".to_string()).unwrap_err();
+        assert_eq!(err.element(), "c");
+        assert_eq!(&*v, &["a", "b"]);
+    }
+
+    #[test]
+    fn insert_remove_pop() {
+        let mut v: ArrayMyVec<i32, 3> = ArrayMyVec::new();
+        v.push(1).unwrap();
+        v.push(3).unwrap();
+        v.insert(1, 2).unwrap();
+        assert_eq!(v.insert(0, 0).unwrap_err().element(), 0);
+        assert_eq!(&*v, &[1, 2, 3]);
+        assert_eq!(v.remove(0), 1);
+        v[0] = 5;
+        assert_eq!(v.pop(), Some(3));
+        assert_eq!(v.pop(), Some(5));
+        assert_eq!(v.pop(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        let mut v: ArrayMyVec<i32, 3> = ArrayMyVec::new();
+        let _ = v.insert(1, 1);
+    }
+
+    #[test]
+    fn drain() {
+        let mut v: ArrayMyVec<i32, 5> = ArrayMyVec::new();
+        for i in 1..=5 {
+            v.push(i).unwrap();
+        }
+        let mut it = v.drain(..);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next_back(), Some(5));
+        assert_eq!(it.next(), Some(2));
+        drop(it);
+        assert!(v.is_empty());
+        v.push(6).unwrap();
+        assert_eq!(&*v, &[6]);
+    }
+
+    #[test]
+    fn drain_range_restores_tail() {
+        let mut v: ArrayMyVec<i32, 5> = ArrayMyVec::new();
+        for i in 1..=5 {
+            v.push(i).unwrap();
+        }
+        assert_eq!(v.drain(1..3).next(), Some(2));
+        assert_eq!(&*v, &[1, 4, 5]);
+        let mut it = v.drain(..);
+        assert_eq!(it.next_back(), Some(5));
+        it.keep_rest();
+        assert_eq!(&*v, &[1, 4]);
+        v.push(6).unwrap();
+        assert_eq!(&*v, &[1, 4, 6]);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut v: ArrayMyVec<i32, 5> = ArrayMyVec::new();
+        for i in 1..=5 {
+            v.push(i).unwrap();
+        }
+        let mut it = v.into_iter();
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next_back(), Some(5));
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next_back(), Some(4));
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    fn iterator_traits() {
+        let mut v: ArrayMyVec<i32, 5> = ArrayMyVec::new();
+        for i in 1..=5 {
+            v.push(i).unwrap();
+        }
+        let mut drain = v.drain(1..4);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back(), Some(4));
+        drain.as_mut_slice()[1] = 30;
+        assert_eq!(std::format!("{:?}", drain), "Drain([2, 30])");
+        drain.keep_rest();
+        assert_eq!(&*v, &[1, 2, 30, 5]);
+
+        let mut iter = v.into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.as_mut_slice()[0] = 20;
+        let clone = iter.clone();
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(std::format!("{:?}", iter), "IntoIter([20, 30])");
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.next(), None);
+        assert_eq!(clone.as_slice(), &[20, 30, 5]);
+        assert_eq!(clone.len(), 3);
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let rc = Rc::new(());
+        {
+            let mut v: ArrayMyVec<Rc<()>, 4> = ArrayMyVec::new();
+            for _ in 0..4 {
+                v.push(rc.clone()).unwrap();
+            }
+            v.remove(0);
+            let mut it = v.into_iter();
+            it.next();
+            assert_eq!(Rc::strong_count(&rc), 3);
+        }
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn zst() {
+        let mut v: ArrayMyVec<(), 3> = ArrayMyVec::new();
+        for _ in 0..3 {
+            v.push(()).unwrap();
+        }
+        assert!(v.push(()).is_err());
+        let mut it = v.drain(..);
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next_back(), Some(()));
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use core::cmp;
+
+/// Decides how far a `MyVec` grows once it runs out of room.
+pub trait GrowthPolicy {
+    /// Returns the capacity a buffer of `cap` elements, each `elem_size` bytes big, should grow
+    /// to when it needs room for `required` elements. `required` is always larger than `cap`,
+    /// and anything returned below it is bumped up to `required`.
+    fn next_capacity(&self, cap: usize, required: usize, elem_size: usize) -> usize;
+}
+
+/// The same heuristic std's `Vec` uses: double the capacity, but never allocate fewer than 8
+/// elements of 1 byte, 4 elements of up to 1KiB or 1 larger element to begin with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DefaultGrowth;
+
+impl GrowthPolicy for DefaultGrowth {
+    fn next_capacity(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        let min_non_zero_cap = if elem_size == 1 {
+            8
+        } else if elem_size <= 1024 {
+            4
+        } else {
+            1
+        };
+        cmp::max(cmp::max(cap.saturating_mul(2), required), min_non_zero_cap)
+    }
+}
+
+/// Plain doubling, starting from a single element.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    fn next_capacity(&self, cap: usize, required: usize, _elem_size: usize) -> usize {
+        cmp::max(cap.saturating_mul(2), required)
+    }
+}
+
+/// Grows by half the current capacity, trading more reallocations for less slack.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OneAndAHalf;
+
+impl GrowthPolicy for OneAndAHalf {
+    fn next_capacity(&self, cap: usize, required: usize, _elem_size: usize) -> usize {
+        cmp::max(cap.saturating_add(cap / 2), required)
+    }
+}
+
+/// Grows by a fixed number of elements at a time. Pushes are O(n) amortized, so this only suits
+/// vectors whose final size is roughly known.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedStep(pub usize);
+
+impl GrowthPolicy for FixedStep {
+    fn next_capacity(&self, cap: usize, required: usize, _elem_size: usize) -> usize {
+        cmp::max(cap.saturating_add(self.0), required)
+    }
+}
+
+/// Doubles, then rounds the buffer up to a whole number of pages so the slack at the end of the
+/// last page isn't wasted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PageRounded {
+    page_size: usize,
+}
+
+impl PageRounded {
+    /// Rounds to multiples of `page_size` bytes, which must be a power of two.
+    pub fn new(page_size: usize) -> Self {
+        assert!(
+            page_size.is_power_of_two(),
+            "page size must be a power of two"
+        );
+        PageRounded { page_size }
+    }
+}
+
+impl Default for PageRounded {
+    fn default() -> Self {
+        PageRounded::new(4096)
+    }
+}
+
+impl GrowthPolicy for PageRounded {
+    fn next_capacity(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        let doubled = cmp::max(cap.saturating_mul(2), required);
+        round_bytes(doubled, elem_size, |bytes| {
+            Some(bytes.checked_add(self.page_size - 1)? & !(self.page_size - 1))
+        })
+    }
+}
+
+/// Doubles, then rounds the buffer up to the next jemalloc-style size class (four classes per
+/// power of two, 16 bytes apart at the smallest), which the allocator would round up to anyway.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SizeClass;
+
+impl GrowthPolicy for SizeClass {
+    fn next_capacity(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        let doubled = cmp::max(cap.saturating_mul(2), required);
+        round_bytes(doubled, elem_size, |bytes| {
+            if bytes <= 8 {
+                return Some(8);
+            }
+            let spacing = cmp::max(bytes.checked_next_power_of_two()? / 8, 16);
+            Some(bytes.checked_add(spacing - 1)? & !(spacing - 1))
+        })
+    }
+}
+
+/// Converts `cap` elements to bytes, rounds them with `round` and converts back, falling back to
+/// `cap` itself whenever the arithmetic overflows (the allocation would fail anyway).
+fn round_bytes(cap: usize, elem_size: usize, round: impl FnOnce(usize) -> Option<usize>) -> usize {
+    if elem_size == 0 {
+        return cap;
+    }
+    cap.checked_mul(elem_size)
+        .and_then(round)
+        .map_or(cap, |bytes| bytes / elem_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_growth_minimums() {
+        assert_eq!(DefaultGrowth.next_capacity(0, 1, 1), 8);
+        assert_eq!(DefaultGrowth.next_capacity(0, 1, 4), 4);
+        assert_eq!(DefaultGrowth.next_capacity(0, 1, 4096), 1);
+        assert_eq!(DefaultGrowth.next_capacity(4, 5, 4), 8);
+        assert_eq!(DefaultGrowth.next_capacity(4, 100, 4), 100);
+    }
+
+    #[test]
+    fn simple_policies() {
+        assert_eq!(Doubling.next_capacity(0, 1, 4), 1);
+        assert_eq!(Doubling.next_capacity(3, 4, 4), 6);
+        assert_eq!(OneAndAHalf.next_capacity(0, 1, 4), 1);
+        assert_eq!(OneAndAHalf.next_capacity(10, 11, 4), 15);
+        assert_eq!(FixedStep(16).next_capacity(0, 1, 4), 16);
+        assert_eq!(FixedStep(16).next_capacity(16, 40, 4), 40);
+        assert_eq!(
+            Doubling.next_capacity(usize::MAX - 1, usize::MAX, 1),
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn page_rounded() {
+        let policy = PageRounded::default();
+        assert_eq!(policy.next_capacity(0, 1, 8), 512);
+        assert_eq!(policy.next_capacity(512, 513, 8), 1024);
+        assert_eq!(policy.next_capacity(0, 1, 3000), 1);
+        assert_eq!(policy.next_capacity(1, 2, 3000), 2);
+        assert_eq!(policy.next_capacity(2, 3, 3000), 4);
+    }
+
+    #[test]
+    fn size_class() {
+        assert_eq!(SizeClass.next_capacity(0, 1, 1), 8);
+        assert_eq!(SizeClass.next_capacity(0, 1, 12), 1);
+        assert_eq!(SizeClass.next_capacity(2, 3, 12), 4);
+        assert_eq!(SizeClass.next_capacity(6, 7, 12), 13);
+        assert_eq!(SizeClass.next_capacity(0, 1, 100), 1);
+        assert_eq!(SizeClass.next_capacity(1, 2, 100), 2);
+        assert_eq!(SizeClass.next_capacity(2, 3, 100), 4);
+    }
+
+    #[test]
+    fn huge_requests_dont_overflow() {
+        let cap = usize::MAX / 4;
+        assert_eq!(
+            PageRounded::default().next_capacity(cap, cap + 1, 8),
+            cap * 2
+        );
+        assert_eq!(SizeClass.next_capacity(cap, cap + 1, 8), cap * 2);
+    }
+}
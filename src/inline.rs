@@ -0,0 +1,210 @@
+//! The `Drain` and `IntoIter` shared by the vectors that can keep their elements inline,
+//! `ArrayMyVec` and `SmallMyVec`.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::{ptr, slice};
+
+use crate::{close_drain_gap, drop_elements, keep_drain_rest, RawValIter};
+
+/// Storage that may live inside the vector itself, so that it moves along with it.
+pub trait InlineStorage<T> {
+    fn ptr(&self) -> *const T;
+
+    fn ptr_mut(&mut self) -> *mut T;
+
+    /// Sets the length to 0 and returns what it was, handing the elements to the caller.
+    fn take_len(&mut self) -> usize;
+
+    /// Makes a new vector out of clones of `items`, which came out of a vector of this type.
+    fn from_cloned(items: &[T]) -> Self
+    where
+        T: Clone;
+}
+
+/// Unlike `MyVec`'s drain, this one keeps the vector's storage and length apart so that the
+/// pointers into inline storage stay valid while the length is updated.
+pub struct Drain<'a, T, V> {
+    base: *mut T,
+    len: &'a mut usize,
+    tail_start: usize,
+    tail_len: usize,
+    iter: RawValIter<T>,
+    _marker: PhantomData<&'a mut V>,
+}
+
+impl<'a, T, V> Drain<'a, T, V> {
+    /// Drains `start..end` out of the `len` elements at `base`, cutting `len` down to `start`
+    /// for as long as the drain lives.
+    pub(crate) unsafe fn new(base: *mut T, len: &'a mut usize, start: usize, end: usize) -> Self {
+        let tail_len = *len - end;
+        *len = start;
+        Drain {
+            base,
+            len,
+            tail_start: end,
+            tail_len,
+            iter: RawValIter::new(base.add(start), end - start),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the elements that haven't been yielded yet.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// Returns the elements that haven't been yielded yet, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.iter.as_mut_slice()
+    }
+
+    /// Stops draining and keeps the elements that haven't been yielded yet in the vector.
+    pub fn keep_rest(self) {
+        let mut this = ManuallyDrop::new(self);
+        let this = &mut *this;
+        unsafe {
+            keep_drain_rest(
+                this.base,
+                this.len,
+                &this.iter,
+                this.tail_start,
+                this.tail_len,
+            )
+        }
+    }
+}
+
+impl<'a, T, V> Iterator for Drain<'a, T, V> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, V> DoubleEndedIterator for Drain<'a, T, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T, V> ExactSizeIterator for Drain<'a, T, V> {}
+
+impl<'a, T, V> FusedIterator for Drain<'a, T, V> {}
+
+impl<'a, T: fmt::Debug, V> fmt::Debug for Drain<'a, T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<'a, T, V> Drop for Drain<'a, T, V> {
+    fn drop(&mut self) {
+        /// Puts the tail back even if dropping one of the drained elements panics
+        struct DropGuard<'r, 'a, T, V>(&'r mut Drain<'a, T, V>);
+
+        impl<'r, 'a, T, V> Drop for DropGuard<'r, 'a, T, V> {
+            fn drop(&mut self) {
+                let d = &mut *self.0;
+                unsafe { close_drain_gap(d.base, d.len, d.tail_start, d.tail_len) }
+            }
+        }
+
+        let guard = DropGuard(self);
+        unsafe { guard.0.iter.drop_remaining() }
+    }
+}
+
+/// The inline elements move along with the iterator, so unlike `MyVec`'s `IntoIter` this one
+/// tracks the remaining elements by index instead of by pointer.
+pub struct IntoIter<T, V: InlineStorage<T>> {
+    vec: V,
+    start: usize,
+    end: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T, V: InlineStorage<T>> IntoIter<T, V> {
+    pub(crate) fn new(mut vec: V) -> Self {
+        // the iterator owns the elements from here on, `vec` only keeps the storage alive
+        let end = vec.take_len();
+        IntoIter {
+            vec,
+            start: 0,
+            end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the elements that haven't been yielded yet.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the elements that haven't been yielded yet, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.end - self.start;
+        unsafe { slice::from_raw_parts_mut(self.vec.ptr_mut().add(self.start), len) }
+    }
+}
+
+impl<T, V: InlineStorage<T>> Iterator for IntoIter<T, V> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            self.start += 1;
+            unsafe { Some(ptr::read(self.vec.ptr().add(self.start - 1))) }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, V: InlineStorage<T>> DoubleEndedIterator for IntoIter<T, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.vec.ptr().add(self.end))) }
+        }
+    }
+}
+
+impl<T, V: InlineStorage<T>> ExactSizeIterator for IntoIter<T, V> {}
+
+impl<T, V: InlineStorage<T>> FusedIterator for IntoIter<T, V> {}
+
+/// Clones the remaining elements into a fresh vector.
+impl<T: Clone, V: InlineStorage<T>> Clone for IntoIter<T, V> {
+    fn clone(&self) -> Self {
+        IntoIter::new(V::from_cloned(self.as_slice()))
+    }
+}
+
+impl<T: fmt::Debug, V: InlineStorage<T>> fmt::Debug for IntoIter<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, V: InlineStorage<T>> Drop for IntoIter<T, V> {
+    fn drop(&mut self) {
+        let (start, end) = (self.start, self.end);
+        self.start = end;
+        unsafe { drop_elements(self.vec.ptr_mut().add(start), end - start) }
+    }
+}
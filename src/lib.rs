@@ -1,77 +1,239 @@
-use std::alloc;
-use std::ops::{Deref, DerefMut};
-use std::slice::from_raw_parts;
-use std::{marker::PhantomData, ptr::NonNull};
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+use alloc::alloc::{handle_alloc_error, Layout};
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
+use core::{marker::PhantomData, ptr::NonNull};
+
+mod allocator;
+pub mod array_vec;
+mod growth;
+mod inline;
+pub mod small_vec;
+
+pub use allocator::{AllocError, Allocator, Global};
+pub use array_vec::{ArrayMyVec, CapacityError};
+pub use growth::{
+    DefaultGrowth, Doubling, FixedStep, GrowthPolicy, OneAndAHalf, PageRounded, SizeClass,
+};
+pub use small_vec::SmallMyVec;
+
+/// The error type for the `try_*` methods that may need to allocate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity overflowed `usize` or exceeded `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The allocator failed to serve the request.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                f.write_str(" because the memory allocator returned an error")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
+/// Resolves `range` against a sequence of `len` elements, panicking like slice indexing when it
+/// doesn't fit.
+fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1).expect("range start overflow"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.checked_add(1).expect("range end overflow"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start must not be greater than end");
+    assert!(end <= len, "range end out of bounds");
+    start..end
+}
+
+/// Maps a failed reservation onto the behaviour of the infallible APIs: panic on capacity
+/// overflow, abort through the global alloc error handler on allocator failure.
+fn handle_reserve<R>(result: Result<R, TryReserveError>) -> R {
+    match result {
+        Ok(r) => r,
+        Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+        Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
+    }
+}
 
 #[derive(Debug)]
-struct RawVec<T> {
+struct RawVec<T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
     /// 1. NonNull<T> will never be Null
     /// 2. NonNull<T> is covariant over T
     ptr: NonNull<T>,
     cap: usize,
+    alloc: A,
+    growth: G,
     /// Pretending to own T for dropck later
     _marker: PhantomData<T>,
 }
-unsafe impl<T: Sync> Sync for RawVec<T> {}
-unsafe impl<T: Send> Send for RawVec<T> {}
+unsafe impl<T: Sync, A: Allocator + Sync, G: GrowthPolicy + Sync> Sync for RawVec<T, A, G> {}
+unsafe impl<T: Send, A: Allocator + Send, G: GrowthPolicy + Send> Send for RawVec<T, A, G> {}
 
 impl<T> RawVec<T> {
     fn new() -> Self {
+        Self::new_in(Global, DefaultGrowth)
+    }
+
+    fn try_with_capacity(cap: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(cap, Global, DefaultGrowth)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
+    fn new_in(alloc: A, growth: G) -> Self {
         // if the size of T is 0, we set the capacity to be i32::MAX
-        let cap = if std::mem::size_of::<T>() == 0 { !0 } else { 0 };
+        let cap = if core::mem::size_of::<T>() == 0 { !0 } else { 0 };
         RawVec {
             ptr: NonNull::dangling(),
             cap,
+            alloc,
+            growth,
             _marker: PhantomData,
         }
     }
 
-    fn grow(&mut self) {
-        assert!(std::mem::size_of::<T>() != 0, "capacity overflow");
-        let new_cap = if self.cap == 0 { 1 } else { self.cap * 2 };
-        let new_layout = alloc::Layout::array::<T>(new_cap).unwrap();
+    fn try_with_capacity_in(cap: usize, alloc: A, growth: G) -> Result<Self, TryReserveError> {
+        let mut buf = Self::new_in(alloc, growth);
+        if cap > buf.cap {
+            buf.try_grow_to(cap)?;
+        }
+        Ok(buf)
+    }
+
+    /// Makes room for one more element once the buffer is full. A zero-sized T starts out with a
+    /// capacity of usize::MAX, so for it this always reports an overflow.
+    fn try_grow(&mut self) -> Result<(), TryReserveError> {
+        self.try_reserve(self.cap, 1)
+    }
+
+    /// Makes room for at least `len + additional` elements, letting the growth policy pick how
+    /// much extra to reserve so that repeated calls stay amortized.
+    fn try_reserve(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let required = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+        let suggested = self
+            .growth
+            .next_capacity(self.cap, required, core::mem::size_of::<T>());
+        self.try_grow_to(core::cmp::max(suggested, required))
+    }
+
+    /// Makes room for exactly `len + additional` elements if the buffer isn't big enough yet.
+    fn try_reserve_exact(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let required = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+        self.try_grow_to(required)
+    }
 
+    fn try_grow_to(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        debug_assert!(new_cap > self.cap);
         // ptr::offset takes an `isize` parameter which is the max number of units of T a pointer
-        // can possibly reach
-        assert!(
-            new_layout.size() <= isize::MAX as usize,
-            "Allocation too large"
-        );
+        // can possibly reach, Layout::array refuses anything larger than isize::MAX bytes for us
+        let new_layout =
+            Layout::array::<T>(new_cap).map_err(|_| TryReserveError::CapacityOverflow)?;
 
         let new_ptr = if self.cap == 0 {
-            unsafe { alloc::alloc(new_layout) }
+            self.alloc.allocate(new_layout)
         } else {
-            let old_layout = alloc::Layout::array::<T>(self.cap).unwrap();
-            let old_ptr = self.ptr.as_ptr() as *mut u8;
-            unsafe { alloc::realloc(old_ptr, old_layout, new_cap) }
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { self.alloc.grow(self.ptr.cast(), old_layout, new_layout) }
         };
 
-        // if allocation failed, None will be returned
-        self.ptr = match NonNull::new(new_ptr as *mut T) {
-            Some(p) => p,
-            None => {
-                alloc::handle_alloc_error(new_layout);
-            }
-        };
+        // if allocation failed, the old buffer is left untouched
+        self.ptr = new_ptr
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+            .cast();
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    /// Shrinks the buffer down to `new_cap` elements, releasing it entirely for a `new_cap` of 0.
+    fn try_shrink_to(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        debug_assert!(new_cap <= self.cap);
+        // a zero-sized T never owns an allocation and keeps its capacity of usize::MAX
+        if core::mem::size_of::<T>() == 0 || new_cap == self.cap {
+            return Ok(());
+        }
+        let old_layout = Layout::array::<T>(self.cap).unwrap();
+        if new_cap == 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), old_layout) };
+            self.ptr = NonNull::dangling();
+        } else {
+            let new_layout = Layout::array::<T>(new_cap).unwrap();
+            let new_ptr = unsafe { self.alloc.shrink(self.ptr.cast(), old_layout, new_layout) };
+            // if shrinking failed, the old buffer is left untouched
+            self.ptr = new_ptr
+                .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+                .cast();
+        }
         self.cap = new_cap;
+        Ok(())
     }
 }
 
-impl<T> Drop for RawVec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for RawVec<T, A, G> {
     fn drop(&mut self) {
-        if self.cap != 0 && std::mem::size_of::<T>() != 0 {
-            let layout = alloc::Layout::array::<T>(self.cap).unwrap();
+        if self.cap != 0 && core::mem::size_of::<T>() != 0 {
+            let layout = Layout::array::<T>(self.cap).unwrap();
             unsafe {
-                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
+                self.alloc.deallocate(self.ptr.cast(), layout);
             }
         }
     }
 }
 
-
+/// A contiguous growable array, generic over where its memory comes from (`A`) and how it grows
+/// (`G`).
+///
+/// # Panics and leaks
+///
+/// Every method leaves the vector valid when a destructor, `Clone` impl, predicate or iterator
+/// of the caller's panics: no element is dropped twice, and none can be reached after it was
+/// moved out. When an element's destructor panics while several are being dropped (dropping the
+/// vector, `truncate`, `clear`, or dropping a `Drain` or `IntoIter`), the remaining elements are
+/// still dropped; a second panic during that aborts, just like it does for slices.
+///
+/// Leaking is safe but amplified: `mem::forget`ting a `Drain`, `Splice` or `ExtractIf` leaks
+/// more than the elements it hadn't yielded yet. The vector's length is cut short while they're
+/// alive, so a forgotten `Drain` or `Splice` also leaks the elements after its range, and a
+/// forgotten `ExtractIf` leaks the whole vector. A panic in the middle of `retain`, `dedup_by`
+/// and friends keeps everything that wasn't looked at yet.
 #[derive(Debug)]
-pub struct MyVec<T> {
-    buf: RawVec<T>,
+pub struct MyVec<T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    buf: RawVec<T, A, G>,
     len: usize,
 }
 
@@ -83,7 +245,103 @@ impl<T> MyVec<T> {
         }
     }
 
-    fn cap(&self) -> usize {
+    /// Creates an empty vector with room for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        handle_reserve(Self::try_with_capacity(capacity))
+    }
+
+    /// Creates an empty vector with room for at least `capacity` elements, reporting allocation
+    /// failure instead of aborting.
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity(capacity)?,
+            len: 0,
+        })
+    }
+
+    /// Rebuilds a vector from the pieces returned by `into_raw_parts`, or taken from a std `Vec`.
+    /// Nothing is copied or reallocated. For a zero-sized T, `capacity` is ignored.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be dangling for a `capacity` of 0, and otherwise point to a block allocated by
+    /// the global allocator with the layout of `[T; capacity]`. The first `len` elements must be
+    /// initialized, `len` must not exceed `capacity`, and nothing else may own the block.
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, capacity: usize) -> Self {
+        let mut buf = RawVec::new();
+        buf.ptr = NonNull::new_unchecked(ptr);
+        if core::mem::size_of::<T>() != 0 {
+            buf.cap = capacity;
+        }
+        MyVec { buf, len }
+    }
+}
+
+impl<T, A: Allocator> MyVec<T, A> {
+    /// Creates an empty vector whose memory will come from `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_growth_in(DefaultGrowth, alloc)
+    }
+
+    /// Creates an empty vector with room for at least `capacity` elements, allocated from
+    /// `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        handle_reserve(Self::try_with_capacity_in(capacity, alloc))
+    }
+
+    /// Like `with_capacity_in`, but reports allocation failure instead of aborting.
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity_in(capacity, alloc, DefaultGrowth)?,
+            len: 0,
+        })
+    }
+}
+
+impl<T, G: GrowthPolicy> MyVec<T, Global, G> {
+    /// Creates an empty vector that grows according to `growth` instead of `DefaultGrowth`.
+    pub fn with_growth(growth: G) -> Self {
+        Self::with_growth_in(growth, Global)
+    }
+
+    /// Takes the vector apart into its pointer, length and capacity without freeing anything.
+    /// `MyVec::from_raw_parts` or `Vec::from_raw_parts` can put the pieces back together.
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let this = ManuallyDrop::new(self);
+        (this.ptr(), this.len, this.capacity())
+    }
+
+    /// Converts the vector into a boxed slice, shrinking the buffer to fit first. This is the only
+    /// reallocation, and it's skipped if the vector is already full.
+    pub fn into_boxed_slice(mut self) -> alloc::boxed::Box<[T]> {
+        self.shrink_to_fit();
+        let (ptr, len, _) = self.into_raw_parts();
+        unsafe { alloc::boxed::Box::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, len)) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> MyVec<T, A, G> {
+    /// Creates an empty vector that grows according to `growth` and allocates from `alloc`.
+    pub fn with_growth_in(growth: G, alloc: A) -> Self {
+        Self {
+            buf: RawVec::new_in(alloc, growth),
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the growth policy.
+    pub fn growth_policy(&self) -> &G {
+        &self.buf.growth
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        &self.buf.alloc
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating. This is always
+    /// `usize::MAX` for zero-sized types.
+    pub fn capacity(&self) -> usize {
         self.buf.cap
     }
 
@@ -91,15 +349,99 @@ impl<T> MyVec<T> {
         self.buf.ptr.as_ptr()
     }
 
+    /// Returns a pointer to the buffer. It's dangling, but well-aligned, while nothing is
+    /// allocated, and may be invalidated by anything that reallocates.
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr()
+    }
+
+    /// Returns a mutable pointer to the buffer, see `as_ptr`.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr()
+    }
+
+    /// Returns the unused capacity past the last element. Initialize a prefix of it, then call
+    /// `set_len` to make those elements part of the vector.
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self.split_at_spare_mut().1
+    }
+
+    /// Returns the elements and the unused capacity at the same time, so the former can be read
+    /// while the latter is being filled.
+    pub fn split_at_spare_mut(&mut self) -> (&mut [T], &mut [MaybeUninit<T>]) {
+        unsafe {
+            let spare = self.ptr().add(self.len) as *mut MaybeUninit<T>;
+            let spare_len = self.capacity() - self.len;
+            (
+                core::slice::from_raw_parts_mut(self.ptr(), self.len),
+                core::slice::from_raw_parts_mut(spare, spare_len),
+            )
+        }
+    }
+
+    /// Sets the length without dropping or initializing anything.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed the capacity, and the first `new_len` elements must be
+    /// initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity(), "new_len exceeds capacity");
+        self.len = new_len;
+    }
+
+    /// Makes room for at least `additional` more elements, possibly reserving more to keep
+    /// pushes amortized O(1).
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional))
+    }
+
+    /// Makes room for exactly `additional` more elements.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve_exact(additional))
+    }
+
+    /// Tries to make room for at least `additional` more elements, possibly reserving more to
+    /// keep pushes amortized O(1).
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.try_reserve(self.len, additional)
+    }
+
+    /// Tries to make room for exactly `additional` more elements.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.try_reserve_exact(self.len, additional)
+    }
+
+    /// Shrinks the capacity down to the length, freeing the buffer entirely if it's empty.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0)
+    }
+
+    /// Shrinks the capacity down to `max(len, min_capacity)`. Does nothing if the capacity is
+    /// already at or below `min_capacity`.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if self.capacity() > min_capacity {
+            let new_cap = core::cmp::max(self.len, min_capacity);
+            handle_reserve(self.buf.try_shrink_to(new_cap))
+        }
+    }
+
     pub fn push(&mut self, ele: T) {
-        if self.len == self.cap() {
-            self.buf.grow();
+        handle_reserve(self.try_push(ele))
+    }
+
+    /// Appends `ele`, returning an error instead of panicking or aborting if the buffer can't
+    /// grow. `ele` is dropped on failure.
+    pub fn try_push(&mut self, ele: T) -> Result<(), TryReserveError> {
+        if self.len == self.capacity() {
+            self.buf.try_grow()?;
         }
         unsafe {
-            std::ptr::write(self.ptr().add(self.len), ele);
+            core::ptr::write(self.ptr().add(self.len), ele);
         }
 
         self.len += 1;
+        Ok(())
     }
 
     pub fn pop(&mut self) -> Option<T> {
@@ -107,23 +449,30 @@ impl<T> MyVec<T> {
             None
         } else {
             self.len -= 1;
-            unsafe { Some(std::ptr::read(self.ptr().add(self.len))) }
+            unsafe { Some(core::ptr::read(self.ptr().add(self.len))) }
         }
     }
 
     pub fn insert(&mut self, idx: usize, ele: T) {
+        handle_reserve(self.try_insert(idx, ele))
+    }
+
+    /// Inserts `ele` at `idx`, returning an error instead of panicking or aborting if the buffer
+    /// can't grow. Still panics if `idx > len`.
+    pub fn try_insert(&mut self, idx: usize, ele: T) -> Result<(), TryReserveError> {
         assert!(idx <= self.len, "index out of bounds");
-        if self.len == self.cap() {
-            self.buf.grow();
+        if self.len == self.capacity() {
+            self.buf.try_grow()?;
         }
         unsafe {
             let ptr = self.ptr().add(idx);
             let new_ptr = self.ptr().add(idx + 1);
             let count = self.len - idx;
-            std::ptr::copy(ptr, new_ptr, count);
-            std::ptr::write(ptr, ele);
+            core::ptr::copy(ptr, new_ptr, count);
+            core::ptr::write(ptr, ele);
             self.len += 1;
         }
+        Ok(())
     }
 
     pub fn remove(&mut self, idx: usize) -> T {
@@ -133,201 +482,1167 @@ impl<T> MyVec<T> {
             let new_ptr = self.ptr().add(idx);
             let item = new_ptr.read();
             let count = self.len - idx - 1;
-            std::ptr::copy(ptr, new_ptr, count);
+            core::ptr::copy(ptr, new_ptr, count);
             self.len -= 1;
-            return item;
+            item
         }
     }
 
-    pub fn into_iter(self) -> IntoIter<T> {
+    /// Removes the element at `idx` in O(1) by moving the last element into its place.
+    ///
+    /// Panics if `idx >= len`.
+    pub fn swap_remove(&mut self, idx: usize) -> T {
+        assert!(idx < self.len, "index out of bounds");
         unsafe {
-            let buf = std::ptr::read(&self.buf);
-            let iter = RawValIter::new(&self);
+            let hole = self.ptr().add(idx);
+            let item = hole.read();
+            self.len -= 1;
+            // when idx is the last index this copies the element onto itself, which is fine
+            core::ptr::copy(self.ptr().add(self.len), hole, 1);
+            item
+        }
+    }
 
-            std::mem::forget(self);
-            // take ownership of self without running its destructor
-            IntoIter {
-                iter,
-                _buf: buf,
-            }
+    /// Like `swap_remove`, but returns `None` instead of panicking if `idx` is out of bounds.
+    pub fn try_swap_remove(&mut self, idx: usize) -> Option<T> {
+        if idx < self.len {
+            Some(self.swap_remove(idx))
+        } else {
+            None
+        }
+    }
+
+    /// Inserts `ele` at `idx` in O(1) by moving the element already there to the end.
+    ///
+    /// Panics if `idx > len`.
+    pub fn swap_insert(&mut self, idx: usize, ele: T) {
+        assert!(idx <= self.len, "index out of bounds");
+        if self.len == self.capacity() {
+            handle_reserve(self.buf.try_grow());
+        }
+        unsafe {
+            let slot = self.ptr().add(idx);
+            core::ptr::copy(slot, self.ptr().add(self.len), 1);
+            core::ptr::write(slot, ele);
+        }
+        self.len += 1;
+    }
+
+    /// Like `swap_insert`, but hands `ele` back instead of panicking if `idx` is out of bounds,
+    /// or panicking or aborting if the vector has to grow and can't.
+    ///
+    /// This returns a `Result` rather than an `Option` so that the element comes back in `Err`:
+    /// an `Option<T>` would be `None` on success, which reads backwards and isn't `must_use`.
+    pub fn try_swap_insert(&mut self, idx: usize, ele: T) -> Result<(), T> {
+        if idx > self.len || (self.len == self.capacity() && self.buf.try_grow().is_err()) {
+            return Err(ele);
+        }
+        // in bounds and with room for one more, so this neither panics nor grows
+        self.swap_insert(idx, ele);
+        Ok(())
+    }
+
+    /// Drops every element from `len` on, keeping the capacity. Does nothing if the vector is
+    /// already `len` elements long or shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail_len = self.len - len;
+        // shorten first: if one of the drops panics, the tail must not be dropped a second time
+        // by our own destructor
+        self.len = len;
+        unsafe { drop_elements(self.ptr().add(len), tail_len) }
+    }
+
+    /// Drops all elements, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Gives up ownership of the buffer, returning the elements as a slice that lives as long as
+    /// the allocator does. The buffer, spare capacity included, is never freed.
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        A: 'a,
+    {
+        let this = ManuallyDrop::new(self);
+        unsafe { core::slice::from_raw_parts_mut(this.ptr(), this.len) }
+    }
+
+    /// Moves all of `other`'s elements to the end of this vector, leaving `other` empty (but
+    /// with its capacity intact).
+    pub fn append(&mut self, other: &mut Self) {
+        let count = other.len;
+        self.reserve(count);
+        unsafe {
+            core::ptr::copy_nonoverlapping(other.ptr(), self.ptr().add(self.len), count);
         }
+        other.len = 0;
+        self.len += count;
     }
 
-    pub fn drain(&mut self) -> Drain<T> {
+    /// Removes the elements in `range`, yielding them from the returned iterator. The elements
+    /// after the range are moved back into place once the iterator is dropped; if it's leaked
+    /// instead, so are they.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, A, G> {
+        let Range { start, end } = slice_range(range, self.len);
         unsafe {
-            let iter = RawValIter::new(&self);
-            // std::mem::forget(self);
-            self.len = 0;
+            let iter = RawValIter::new(self.ptr().add(start), end - start);
+            let tail_len = self.len - end;
+            // anything from `start` on is owned by the drain now, so a leaked drain can at
+            // worst leak elements, never expose moved-out ones
+            self.len = start;
             Drain {
                 iter,
-                vec: PhantomData
+                tail_start: end,
+                tail_len,
+                vec: NonNull::from(self),
+                _marker: PhantomData
             }
         }
     }
-}
 
-impl<T> Deref for MyVec<T> {
-    type Target = [T];
+    /// Replaces the elements in `range` with the items of `replace_with`, yielding the removed
+    /// elements from the returned iterator. The replacement happens when the iterator is
+    /// dropped, and only moves the tail once if `replace_with` reports an exact size hint.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A, G>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    /// Keeps only the elements for which `f` returns true, in their original order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|ele| f(ele))
     }
-}
 
-impl<T> DerefMut for MyVec<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
+    /// Like `retain`, but `f` gets to mutate the elements it keeps. Every element is visited
+    /// exactly once and moved at most once.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let original_len = self.len;
+        // a panic in `f` or in a drop would otherwise leave holes inside 0..len, the guard
+        // restores the length once the holes are closed
+        self.len = 0;
+
+        // [kept, kept, hole, hole, unchecked, unchecked]
+        //  |<- processed_len ->|  ^ next to check
+        //              |<-del->|
+        struct BackshiftOnDrop<'v, T, A: Allocator, G: GrowthPolicy> {
+            v: &'v mut MyVec<T, A, G>,
+            processed_len: usize,
+            deleted_cnt: usize,
+            original_len: usize,
+        }
+
+        impl<'v, T, A: Allocator, G: GrowthPolicy> Drop for BackshiftOnDrop<'v, T, A, G> {
+            fn drop(&mut self) {
+                if self.deleted_cnt > 0 {
+                    // the unchecked elements haven't been touched, shift them over the holes
+                    unsafe {
+                        let src = self.v.ptr().add(self.processed_len);
+                        let dst = src.sub(self.deleted_cnt);
+                        core::ptr::copy(src, dst, self.original_len - self.processed_len);
+                    }
+                }
+                self.v.len = self.original_len - self.deleted_cnt;
+            }
+        }
+
+        let mut g = BackshiftOnDrop {
+            v: self,
+            processed_len: 0,
+            deleted_cnt: 0,
+            original_len,
+        };
+
+        while g.processed_len != original_len {
+            let cur = unsafe { &mut *g.v.ptr().add(g.processed_len) };
+            // if `f` panics, `cur` is still unprocessed and the guard keeps it; a rejected one
+            // is counted as gone before it's dropped, so a panicking drop can't drop it again
+            if !f(cur) {
+                g.processed_len += 1;
+                g.deleted_cnt += 1;
+                unsafe { core::ptr::drop_in_place(cur) };
+                continue;
+            }
+            if g.deleted_cnt > 0 {
+                unsafe {
+                    let hole = g.v.ptr().add(g.processed_len - g.deleted_cnt);
+                    core::ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+            g.processed_len += 1;
+        }
     }
-}
 
-impl<T> Drop for MyVec<T> {
-    fn drop(&mut self) {
-        // this could be removed when T:!Drop as in the elements don't need to be dropped
-        while let Some(_) = self.pop() {}
+    /// Returns an iterator that removes and yields the elements in `range` for which `filter`
+    /// returns true, moving the kept ones down as it goes. Elements the iterator doesn't get to
+    /// before it's dropped are kept.
+    pub fn extract_if<F, R>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A, G>
+    where
+        F: FnMut(&mut T) -> bool,
+        R: RangeBounds<usize>,
+    {
+        let old_len = self.len;
+        let Range { start, end } = slice_range(range, old_len);
+        // like with drain, a leaked ExtractIf leaks the elements instead of exposing holes
+        self.len = 0;
+        ExtractIf {
+            vec: self,
+            idx: start,
+            end,
+            del: 0,
+            old_len,
+            pred: filter,
+        }
+    }
+
+    /// Removes consecutive elements that map to the same key, keeping the first of each run.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b))
     }
-}
 
+    /// Removes consecutive elements for which `same_bucket(current, previous_kept)` returns
+    /// true, keeping the first of each run. Done in a single pass, moving every kept element at
+    /// most once.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let len = self.len;
+        if len <= 1 {
+            return;
+        }
 
-pub struct RawValIter<T> {
-    start: *const T,
-    end: *const T
-}
+        // [kept, kept, hole, hole, unchecked, unchecked]
+        //            ^ write     ^ read
+        struct FillGapOnDrop<'v, T, A: Allocator, G: GrowthPolicy> {
+            v: &'v mut MyVec<T, A, G>,
+            read: usize,
+            write: usize,
+            len: usize,
+        }
 
-impl<T> RawValIter<T> {
-    unsafe fn new(slice:&[T]) -> Self {
-        Self {
-            start: slice.as_ptr(),
-            end: if std::mem::size_of::<T>() == 0 {
-                ((slice.as_ptr() as usize) + slice.len()) as *const _
-            } else if slice.len() == 0 {
-                slice.as_ptr()
-            } else {
-                slice.as_ptr().add(slice.len())
+        impl<'v, T, A: Allocator, G: GrowthPolicy> Drop for FillGapOnDrop<'v, T, A, G> {
+            fn drop(&mut self) {
+                // only runs for real when `same_bucket` or a drop panicked: move the unchecked
+                // elements over the holes so the vector stays contiguous
+                unsafe {
+                    let base = self.v.ptr();
+                    let unchecked = self.len - self.read;
+                    core::ptr::copy(base.add(self.read), base.add(self.write), unchecked);
+                    self.v.len = self.write + unchecked;
+                }
             }
         }
-    }
-}
 
-impl<T> Iterator for RawValIter<T> {
-    type Item = T;
+        // like retain, the guard owns the length while there are holes in the buffer
+        self.len = 0;
+        let mut g = FillGapOnDrop {
+            v: self,
+            read: 1,
+            write: 1,
+            len,
+        };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
-            None
-        } else {
+        while g.read < len {
             unsafe {
-                let result = std::ptr::read(self.start);
-                self.start = if std::mem::size_of::<T>() == 0 {
-                    ((self.start as usize) + 1) as *const _
+                let base = g.v.ptr();
+                let cur = base.add(g.read);
+                let prev = base.add(g.write - 1);
+                if same_bucket(&mut *cur, &mut *prev) {
+                    // step past the duplicate first, so a panicking drop doesn't drop it twice
+                    g.read += 1;
+                    core::ptr::drop_in_place(cur);
                 } else {
-                    self.start.add(1)
-                };
-                Some(result)
+                    if g.read != g.write {
+                        core::ptr::copy_nonoverlapping(cur, base.add(g.write), 1);
+                    }
+                    g.write += 1;
+                    g.read += 1;
+                }
             }
         }
     }
 }
 
-impl<T> DoubleEndedIterator for RawValIter<T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
-            None
-        } else {
-            unsafe {
-                self.end = if std::mem::size_of::<T>() == 0 {
-                    ((self.end as usize) - 1 ) as *const _
-                } else {
-                    self.end.sub(1)
-                };
-                Some(std::ptr::read(self.end))
-            }
+impl<T, A: Allocator + Clone, G: GrowthPolicy + Clone> MyVec<T, A, G> {
+    /// Splits the vector in two at `at`, returning the elements from `at` on in a new vector
+    /// that uses a clone of this one's allocator and growth policy.
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "index out of bounds");
+        let count = self.len - at;
+        let buf = handle_reserve(RawVec::try_with_capacity_in(
+            count,
+            self.buf.alloc.clone(),
+            self.buf.growth.clone(),
+        ));
+        let mut other = MyVec { buf, len: 0 };
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.ptr().add(at), other.ptr(), count);
         }
+        self.len = at;
+        other.len = count;
+        other
     }
 }
 
-pub struct Drain<'a,T> {
-    vec: PhantomData<&'a mut MyVec<T>>,
-    iter: RawValIter<T>
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> MyVec<T, A, G> {
+    /// Removes consecutive equal elements, keeping the first of each run. On a sorted vector
+    /// this leaves no duplicates at all.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
 }
 
-impl<'a, T> Iterator for Drain<'a, T> {
-    type Item = T;
+impl<T: Clone, A: Allocator, G: GrowthPolicy> MyVec<T, A, G> {
+    /// Clones and appends every element of `other`, reserving room for all of them up front.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.reserve(other.len());
+        for ele in other {
+            // the length is bumped after every write, so a panicking clone leaves only the
+            // elements cloned so far behind
+            unsafe { core::ptr::write(self.ptr().add(self.len), ele.clone()) };
+            self.len += 1;
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+    /// Clones the elements in `range` and appends them to the end of the vector.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, range: R) {
+        let Range { start, end } = slice_range(range, self.len);
+        self.reserve(end - start);
+        // the buffer can't move from here on, so the source stays valid while we append
+        for idx in start..end {
+            unsafe {
+                let ele = (*self.ptr().add(idx)).clone();
+                core::ptr::write(self.ptr().add(self.len), ele);
+            }
+            self.len += 1;
+        }
     }
 }
 
-impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back()
+impl<T, A: Allocator, G: GrowthPolicy> Deref for MyVec<T, A, G> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.ptr(), self.len) }
     }
 }
 
-impl<'a, T> Drop for Drain<'a, T> {
-    fn drop(&mut self) {
-        for _ in &mut *self { }
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for MyVec<T, A, G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr(), self.len) }
     }
 }
 
-pub struct IntoIter<T> {
-    _buf: RawVec<T>,
-    iter: RawValIter<T>
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for MyVec<T, A, G> {
+    fn clone(&self) -> Self {
+        let buf = handle_reserve(RawVec::try_with_capacity_in(
+            self.len,
+            self.buf.alloc.clone(),
+            self.buf.growth.clone(),
+        ));
+        let mut v = MyVec { buf, len: 0 };
+        v.extend_from_slice(self);
+        v
+    }
+
+    /// Reuses this vector's allocation: the common prefix is cloned over in place, and the buffer
+    /// only grows if `source` is longer than the current capacity.
+    fn clone_from(&mut self, source: &Self) {
+        self.truncate(source.len);
+        let (init, tail) = source.split_at(self.len);
+        self.clone_from_slice(init);
+        self.extend_from_slice(tail);
+    }
 }
 
-impl<T> Iterator for IntoIter<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> { self.iter.next() }
+impl<T, A: Allocator + Default, G: GrowthPolicy + Default> Default for MyVec<T, A, G> {
+    fn default() -> Self {
+        Self::with_growth_in(G::default(), A::default())
+    }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
-    fn next_back(&mut self) -> Option<Self::Item> { self.iter.next_back() }
+macro_rules! impl_slice_eq {
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
+        where
+            T: PartialEq<U>,
+        {
+            fn eq(&self, other: &$rhs) -> bool {
+                self[..] == other[..]
+            }
+        }
+    };
 }
 
-impl<T> Drop for IntoIter<T> {
-    fn drop(&mut self) {
-        for _ in &mut *self {}
-    }
+impl_slice_eq! {
+    [A: Allocator, G: GrowthPolicy, A2: Allocator, G2: GrowthPolicy]
+    MyVec<T, A, G>, MyVec<U, A2, G2>
 }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] MyVec<T, A, G>, [U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] MyVec<T, A, G>, &[U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] MyVec<T, A, G>, &mut [U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy, const N: usize] MyVec<T, A, G>, [U; N] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy, const N: usize] MyVec<T, A, G>, &[U; N] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] MyVec<T, A, G>, alloc::vec::Vec<U> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] [T], MyVec<U, A, G> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] &[T], MyVec<U, A, G> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] alloc::vec::Vec<T>, MyVec<U, A, G> }
 
-#[cfg(test)]
-mod tests {
-    use super::MyVec;
-    #[test]
-    fn create_new_success() {
-        let v: MyVec<i32> = MyVec::new();
-        assert!(std::mem::size_of_val(&v) != 0);
-    }
-    #[test]
-    #[should_panic]
-    fn create_new_fail() {
-        let v: MyVec<()> = MyVec::new();
-        assert!(std::mem::size_of_val(&v) == 0);
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for MyVec<T, A, G> {}
+
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for MyVec<T, A, G> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (**self).partial_cmp(&**other)
     }
+}
 
-    #[test]
-    fn push() {
-        let mut v: MyVec<i32> = MyVec::new();
-        assert_eq!(v.len(), 0);
-        v.push(1);
-        assert_eq!(v.len(), 1);
-        v.push(2);
-        assert_eq!(v.len(), 2);
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for MyVec<T, A, G> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (**self).cmp(&**other)
     }
+}
 
-    #[test]
-    fn pop() {
-        let mut v: MyVec<i32> = MyVec::new();
-        v.push(1);
-        v.push(2);
-        assert_eq!(v.pop(), Some(2));
-        assert_eq!(v.pop(), Some(1));
-        assert_eq!(v.pop(), None);
+/// Hashes exactly like the equivalent slice.
+impl<T: core::hash::Hash, A: Allocator, G: GrowthPolicy> core::hash::Hash for MyVec<T, A, G> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
     }
+}
 
-    #[test]
-    fn deref_to_slice_inbound() {
-        let mut v: MyVec<i32> = MyVec::new();
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for MyVec<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A, G>;
+
+    fn into_iter(self) -> IntoIter<T, A, G> {
+        unsafe {
+            let buf = core::ptr::read(&self.buf);
+            let iter = RawValIter::new(self.ptr(), self.len);
+
+            core::mem::forget(self);
+            // take ownership of self without running its destructor
+            IntoIter {
+                iter,
+                _buf: buf,
+            }
+        }
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a MyVec<T, A, G> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a mut MyVec<T, A, G> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> MyVec<T, A, G> {
+    /// Appends everything `iter` yields. An exact size hint (as slices, ranges and our own
+    /// iterators give) is reserved in one go and then filled by a loop that never reallocates;
+    /// otherwise the lower bound is reserved up front and again whenever the buffer fills up.
+    fn extend_iter<I: Iterator<Item = T>>(&mut self, mut iter: I) {
+        let (lower, upper) = iter.size_hint();
+        self.reserve(lower);
+        if upper == Some(lower) {
+            let end = self.len + lower;
+            while self.len < end {
+                match iter.next() {
+                    Some(ele) => {
+                        unsafe { core::ptr::write(self.ptr().add(self.len), ele) };
+                        self.len += 1;
+                    }
+                    None => return,
+                }
+            }
+        }
+        // either the hint was loose, or an iterator that lied about its length has more to give
+        while let Some(ele) = iter.next() {
+            if self.len == self.capacity() {
+                let (lower, _) = iter.size_hint();
+                self.reserve(lower.saturating_add(1));
+            }
+            unsafe { core::ptr::write(self.ptr().add(self.len), ele) };
+            self.len += 1;
+        }
+    }
+}
+
+impl<T, A: Allocator + Default, G: GrowthPolicy + Default> core::iter::FromIterator<T>
+    for MyVec<T, A, G>
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::default();
+        v.extend_iter(iter.into_iter());
+        v
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for MyVec<T, A, G> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_iter(iter.into_iter())
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> Extend<&'a T> for MyVec<T, A, G> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend_iter(iter.into_iter().copied())
+    }
+}
+
+/// Takes over the `Vec`'s buffer as is, without copying.
+impl<T> From<alloc::vec::Vec<T>> for MyVec<T> {
+    fn from(v: alloc::vec::Vec<T>) -> Self {
+        let mut v = ManuallyDrop::new(v);
+        unsafe { MyVec::from_raw_parts(v.as_mut_ptr(), v.len(), v.capacity()) }
+    }
+}
+
+/// Hands the buffer over to a `Vec` as is, without copying.
+impl<T, G: GrowthPolicy> From<MyVec<T, Global, G>> for alloc::vec::Vec<T> {
+    fn from(v: MyVec<T, Global, G>) -> Self {
+        let (ptr, len, cap) = v.into_raw_parts();
+        unsafe { alloc::vec::Vec::from_raw_parts(ptr, len, cap) }
+    }
+}
+
+/// Takes over the boxed slice's allocation, without copying.
+impl<T> From<alloc::boxed::Box<[T]>> for MyVec<T> {
+    fn from(b: alloc::boxed::Box<[T]>) -> Self {
+        let len = b.len();
+        let ptr = alloc::boxed::Box::into_raw(b) as *mut T;
+        unsafe { MyVec::from_raw_parts(ptr, len, len) }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for MyVec<T> {
+    fn from(items: [T; N]) -> Self {
+        let mut v = MyVec::with_capacity(N);
+        let items = ManuallyDrop::new(items);
+        unsafe {
+            core::ptr::copy_nonoverlapping(items.as_ptr(), v.ptr(), N);
+        }
+        v.len = N;
+        v
+    }
+}
+
+impl<T: Clone> From<&[T]> for MyVec<T> {
+    fn from(items: &[T]) -> Self {
+        let mut v = MyVec::with_capacity(items.len());
+        v.extend_from_slice(items);
+        v
+    }
+}
+
+/// Creates a `MyVec` from a list of elements, or from `[elem; n]` by cloning `elem`. Either way
+/// the buffer is allocated once, at exactly the right capacity.
+///
+/// ```
+/// use my_vec::my_vec;
+///
+/// let v = my_vec![1, 2, 3];
+/// assert_eq!(v, [1, 2, 3]);
+/// let zeros = my_vec![0u8; 4];
+/// assert_eq!(zeros, [0, 0, 0, 0]);
+/// ```
+#[macro_export]
+macro_rules! my_vec {
+    () => {
+        $crate::MyVec::new()
+    };
+    ($elem:expr; $n:expr) => {
+        $crate::__from_elem($elem, $n)
+    };
+    ($($x:expr),+ $(,)?) => {
+        <$crate::MyVec<_>>::from([$($x),+])
+    };
+}
+
+#[doc(hidden)]
+pub fn __from_elem<T: Clone>(elem: T, n: usize) -> MyVec<T> {
+    let mut v = MyVec::with_capacity(n);
+    if n == 0 {
+        return v;
+    }
+    for _ in 1..n {
+        unsafe { core::ptr::write(v.ptr().add(v.len), elem.clone()) };
+        v.len += 1;
+    }
+    // the last slot gets `elem` itself, saving a clone
+    unsafe { core::ptr::write(v.ptr().add(v.len), elem) };
+    v.len += 1;
+    v
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator, G: GrowthPolicy> std::io::Write for MyVec<u8, A, G> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.try_reserve(buf.len())
+            .map_err(|_| std::io::ErrorKind::OutOfMemory)?;
+        unsafe {
+            core::ptr::copy_nonoverlapping(buf.as_ptr(), self.ptr().add(self.len), buf.len());
+        }
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for MyVec<T, A, G> {
+    fn drop(&mut self) {
+        // the buffer itself is freed by RawVec afterwards
+        unsafe { drop_elements(self.ptr(), self.len) }
+    }
+}
+
+
+pub struct RawValIter<T> {
+    start: *mut T,
+    end: *mut T
+}
+
+impl<T> RawValIter<T> {
+    /// Takes over the `len` elements at `ptr`. The pointer has to come straight from the buffer,
+    /// not from a shared slice, since the iterator drops and hands out `&mut` to what's left.
+    unsafe fn new(ptr: *mut T, len: usize) -> Self {
+        Self {
+            start: ptr,
+            // for a zero-sized T the pointers only count the remaining elements, byte by byte
+            end: if core::mem::size_of::<T>() == 0 {
+                (ptr as *mut u8).wrapping_add(len) as *mut T
+            } else {
+                ptr.add(len)
+            }
+        }
+    }
+
+    /// The number of elements not yielded yet
+    fn remaining(&self) -> usize {
+        let elem_size = core::mem::size_of::<T>();
+        (self.end as usize - self.start as usize) / if elem_size == 0 { 1 } else { elem_size }
+    }
+
+    /// Where the remaining elements start. For a zero-sized T `start` is only a counter and may
+    /// be misaligned, so a well-aligned dangling pointer stands in for it.
+    fn data_ptr(&self) -> *mut T {
+        if core::mem::size_of::<T>() == 0 {
+            NonNull::dangling().as_ptr()
+        } else {
+            self.start
+        }
+    }
+
+    fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.data_ptr(), self.remaining()) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.data_ptr(), self.remaining()) }
+    }
+
+    /// Drops the elements that haven't been yielded yet. The iterator is emptied first, so none
+    /// of them can be reached afterwards, panic or not.
+    unsafe fn drop_remaining(&mut self) {
+        let (ptr, len) = (self.data_ptr(), self.remaining());
+        self.start = self.end;
+        drop_elements(ptr, len);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RawValIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RawValIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T> Iterator for RawValIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                let result = core::ptr::read(self.data_ptr());
+                self.start = if core::mem::size_of::<T>() == 0 {
+                    (self.start as *mut u8).wrapping_add(1) as *mut T
+                } else {
+                    self.start.add(1)
+                };
+                Some(result)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for RawValIter<T> {}
+
+impl<T> FusedIterator for RawValIter<T> {}
+
+impl<T> DoubleEndedIterator for RawValIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                if core::mem::size_of::<T>() == 0 {
+                    self.end = (self.end as *mut u8).wrapping_sub(1) as *mut T;
+                    Some(core::ptr::read(self.data_ptr()))
+                } else {
+                    self.end = self.end.sub(1);
+                    Some(core::ptr::read(self.end))
+                }
+            }
+        }
+    }
+}
+
+/// Drops the `len` elements at `ptr` in one go, and doesn't even look at them if `T` has no drop
+/// glue. Like any slice drop it carries on with the rest if one of the destructors panics; a
+/// second panic aborts.
+unsafe fn drop_elements<T>(ptr: *mut T, len: usize) {
+    if core::mem::needs_drop::<T>() {
+        core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(ptr, len));
+    }
+}
+
+/// Moves the `tail_len` elements at `tail_start` down to `*len`, closing the gap a drain left
+/// behind, and counts them back into `len`.
+unsafe fn close_drain_gap<T>(base: *mut T, len: &mut usize, tail_start: usize, tail_len: usize) {
+    if tail_len > 0 {
+        if tail_start != *len {
+            core::ptr::copy(base.add(tail_start), base.add(*len), tail_len);
+        }
+        *len += tail_len;
+    }
+}
+
+/// Moves the elements `iter` hasn't yielded yet down to `*len`, then closes the gap behind them.
+unsafe fn keep_drain_rest<T>(
+    base: *mut T,
+    len: &mut usize,
+    iter: &RawValIter<T>,
+    tail_start: usize,
+    tail_len: usize,
+) {
+    let unyielded = iter.remaining();
+    if core::mem::size_of::<T>() != 0 {
+        core::ptr::copy(iter.start, base.add(*len), unyielded);
+    }
+    *len += unyielded;
+    close_drain_gap(base, len, tail_start, tail_len);
+}
+
+pub struct Drain<'a, T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    /// The drained vector, its length is cut down to the start of the range while draining
+    vec: NonNull<MyVec<T, A, G>>,
+    _marker: PhantomData<&'a mut MyVec<T, A, G>>,
+    /// The elements after the drained range
+    tail_start: usize,
+    tail_len: usize,
+    iter: RawValIter<T>
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drain<'a, T, A, G> {
+    /// Returns a reference to the allocator of the vector being drained.
+    pub fn allocator(&self) -> &A {
+        unsafe { self.vec.as_ref().allocator() }
+    }
+
+    /// Returns the elements that haven't been yielded yet.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// Returns the elements that haven't been yielded yet, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.iter.as_mut_slice()
+    }
+
+    /// Stops draining and keeps the elements that haven't been yielded yet in the vector.
+    pub fn keep_rest(self) {
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            let vec = this.vec.as_mut();
+            let (tail_start, tail_len) = (this.tail_start, this.tail_len);
+            keep_drain_rest(vec.ptr(), &mut vec.len, &this.iter, tail_start, tail_len);
+        }
+    }
+
+    /// Writes items from `replace_with` into the gap between the vector's length and the tail.
+    /// Returns whether the gap could be filled completely.
+    unsafe fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        let vec = self.vec.as_mut();
+        while vec.len < self.tail_start {
+            match replace_with.next() {
+                Some(item) => {
+                    core::ptr::write(vec.ptr().add(vec.len), item);
+                    vec.len += 1;
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Moves the tail back by `additional` slots to widen the gap, growing the buffer if needed.
+    unsafe fn move_tail(&mut self, additional: usize) {
+        let vec = self.vec.as_mut();
+        let len = self.tail_start + self.tail_len;
+        handle_reserve(vec.buf.try_reserve(len, additional));
+        // the buffer may have moved, but all drained elements have been yielded by now
+        self.iter = RawValIter::new(NonNull::dangling().as_ptr(), 0);
+
+        let new_tail_start = self.tail_start + additional;
+        let src = vec.ptr().add(self.tail_start);
+        core::ptr::copy(src, vec.ptr().add(new_tail_start), self.tail_len);
+        self.tail_start = new_tail_start;
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'a, T, A, G> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'a, T, A, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Drain<'a, T, A, G> {}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> FusedIterator for Drain<'a, T, A, G> {}
+
+impl<'a, T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Drain<'a, T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drop for Drain<'a, T, A, G> {
+    fn drop(&mut self) {
+        /// Puts the tail back even if dropping one of the drained elements panics
+        struct DropGuard<'r, 'a, T, A: Allocator, G: GrowthPolicy>(&'r mut Drain<'a, T, A, G>);
+
+        impl<'r, 'a, T, A: Allocator, G: GrowthPolicy> Drop for DropGuard<'r, 'a, T, A, G> {
+            fn drop(&mut self) {
+                unsafe {
+                    let vec = self.0.vec.as_mut();
+                    close_drain_gap(vec.ptr(), &mut vec.len, self.0.tail_start, self.0.tail_len);
+                }
+            }
+        }
+
+        let guard = DropGuard(self);
+        unsafe { guard.0.iter.drop_remaining() }
+    }
+}
+
+/// Removes and yields the elements matching a predicate, see `MyVec::extract_if`.
+pub struct ExtractIf<'a, T, F, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    vec: &'a mut MyVec<T, A, G>,
+    /// The next element to check
+    idx: usize,
+    /// The end of the range to check, elements from here on are always kept
+    end: usize,
+    /// The number of elements extracted so far, i.e. how far kept ones have to move down
+    del: usize,
+    old_len: usize,
+    pred: F,
+}
+
+impl<'a, T, F, A, G> Iterator for ExtractIf<'a, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+    A: Allocator,
+    G: GrowthPolicy,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.idx < self.end {
+            let i = self.idx;
+            let cur = unsafe { &mut *self.vec.ptr().add(i) };
+            let extracted = (self.pred)(cur);
+            // only step past the element once the predicate returned, so a panic keeps it
+            self.idx += 1;
+            if extracted {
+                self.del += 1;
+                return Some(unsafe { core::ptr::read(cur) });
+            } else if self.del > 0 {
+                unsafe {
+                    let hole = self.vec.ptr().add(i - self.del);
+                    core::ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.idx))
+    }
+}
+
+impl<'a, T, F, A: Allocator, G: GrowthPolicy> Drop for ExtractIf<'a, T, F, A, G> {
+    fn drop(&mut self) {
+        unsafe {
+            if self.idx < self.old_len && self.del > 0 {
+                let src = self.vec.ptr().add(self.idx);
+                let dst = src.sub(self.del);
+                core::ptr::copy(src, dst, self.old_len - self.idx);
+            }
+        }
+        self.vec.len = self.old_len - self.del;
+    }
+}
+
+/// Yields the removed range of a `MyVec::splice` and writes the replacement into its place once
+/// dropped.
+pub struct Splice<'a, I: Iterator, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    drain: Drain<'a, I::Item, A, G>,
+    replace_with: I,
+}
+
+impl<'a, I: Iterator, A: Allocator, G: GrowthPolicy> Iterator for Splice<'a, I, A, G> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<'a, I: Iterator, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Splice<'a, I, A, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<'a, I: Iterator, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Splice<'a, I, A, G> {}
+
+impl<'a, I: Iterator, A: Allocator, G: GrowthPolicy> Drop for Splice<'a, I, A, G> {
+    fn drop(&mut self) {
+        // drop whatever hasn't been yielded of the removed range first
+        for _ in &mut self.drain {}
+
+        unsafe {
+            if self.drain.tail_len == 0 {
+                self.drain.vec.as_mut().extend_iter(&mut self.replace_with);
+                return;
+            }
+
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+
+            // when the size hint is exact this makes room for everything that's left in one go
+            let (lower, _) = self.replace_with.size_hint();
+            if lower > 0 {
+                self.drain.move_tail(lower);
+                if !self.drain.fill(&mut self.replace_with) {
+                    return;
+                }
+            }
+
+            // the size hint was too low, collect the rest to learn the exact count
+            let mut rest: MyVec<I::Item> = MyVec::new();
+            for item in &mut self.replace_with {
+                rest.push(item);
+            }
+            if !rest.is_empty() {
+                self.drain.move_tail(rest.len());
+                let filled = self.drain.fill(&mut rest.into_iter());
+                debug_assert!(filled);
+            }
+        }
+        // dropping the drain moves the tail back next to the replacement
+    }
+}
+
+pub struct IntoIter<T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    _buf: RawVec<T, A, G>,
+    iter: RawValIter<T>
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIter<T, A, G> {
+    /// Returns a reference to the allocator the remaining elements live in.
+    pub fn allocator(&self) -> &A {
+        &self._buf.alloc
+    }
+
+    /// Returns the elements that haven't been yielded yet.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// Returns the elements that haven't been yielded yet, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.iter.as_mut_slice()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for IntoIter<T, A, G> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> { self.iter.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for IntoIter<T, A, G> {
+    fn next_back(&mut self) -> Option<Self::Item> { self.iter.next_back() }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for IntoIter<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> FusedIterator for IntoIter<T, A, G> {}
+
+/// Clones the remaining elements into a fresh buffer from a clone of the allocator.
+impl<T, A, G> Clone for IntoIter<T, A, G>
+where
+    T: Clone,
+    A: Allocator + Clone,
+    G: GrowthPolicy + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut v = MyVec::with_growth_in(self._buf.growth.clone(), self._buf.alloc.clone());
+        v.extend_from_slice(self.as_slice());
+        v.into_iter()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for IntoIter<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for IntoIter<T, A, G> {
+    fn drop(&mut self) {
+        // `_buf` frees the buffer afterwards, even if an element panics
+        unsafe { self.iter.drop_remaining() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AllocError, Allocator, Doubling, FixedStep, Global, GrowthPolicy, MyVec, OneAndAHalf,
+        TryReserveError,
+    };
+    use std::alloc::Layout;
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+    use std::rc::Rc;
+
+    /// Forwards to `Global`, keeping track of live blocks and refusing anything above `limit` bytes
+    struct CountingAlloc {
+        live: Cell<usize>,
+        limit: usize,
+    }
+
+    impl CountingAlloc {
+        fn new(limit: usize) -> Self {
+            CountingAlloc {
+                live: Cell::new(0),
+                limit,
+            }
+        }
+    }
+
+    unsafe impl Allocator for CountingAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            if layout.size() > self.limit {
+                return Err(AllocError);
+            }
+            let ptr = Global.allocate(layout)?;
+            self.live.set(self.live.get() + 1);
+            Ok(ptr)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn create_new_success() {
+        let v: MyVec<i32> = MyVec::new();
+        assert!(std::mem::size_of_val(&v) != 0);
+    }
+    #[test]
+    #[should_panic]
+    fn create_new_fail() {
+        let v: MyVec<()> = MyVec::new();
+        assert!(std::mem::size_of_val(&v) == 0);
+    }
+
+    #[test]
+    fn push() {
+        let mut v: MyVec<i32> = MyVec::new();
+        assert_eq!(v.len(), 0);
+        v.push(1);
+        assert_eq!(v.len(), 1);
+        v.push(2);
+        assert_eq!(v.len(), 2);
+    }
+
+    #[test]
+    fn pop() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        assert_eq!(v.pop(), Some(2));
+        assert_eq!(v.pop(), Some(1));
+        assert_eq!(v.pop(), None);
+    }
+
+    #[test]
+    fn deref_to_slice_inbound() {
+        let mut v: MyVec<i32> = MyVec::new();
         v.push(1);
         v.push(2);
         v.push(3);
@@ -354,115 +1669,1098 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn deref_mut_outbound() {
+    #[should_panic]
+    fn deref_mut_outbound() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v[4] = 4;
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.insert(2, 9);
+        assert_eq!(v[3], 3);
+        assert_eq!(v[2], 9);
+        assert_eq!(v.len(), 4);
+    }
+
+    #[test]
+    fn test_remove_success() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        assert_eq!(v.remove(0), 1);
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0], 2);
+    }
+
+    #[should_panic]
+    #[test]
+    fn test_remove_fail() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.remove(2);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.push(4);
+        v.push(5);
+        let mut it = v.into_iter();
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next_back(), Some(5));
+        assert_eq!(it.next_back(), Some(4));
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.push(4);
+        v.push(5);
+        let mut it = v.drain(..);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next_back(), Some(5));
+        assert_eq!(it.next_back(), Some(4));
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    fn test_zst_drain() {
+        let mut v: MyVec<()> = MyVec::new();
+        v.push(());
+        v.push(());
+        v.push(());
+        v.push(());
+        v.push(());
+        let mut it = v.drain(..);
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next_back(), Some(()));
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next_back(), Some(()));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    fn test_zst_iter() {
+        let mut v: MyVec<()> = MyVec::new();
+        v.push(());
+        v.push(());
+        v.push(());
+        v.push(());
+        v.push(());
+        let mut it = v.into_iter();
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next_back(), Some(()));
+        assert_eq!(it.next(), Some(()));
+        assert_eq!(it.next_back(), Some(()));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    fn test_push_many_keeps_contents() {
+        let mut v: MyVec<u64> = MyVec::new();
+        for i in 0..1000 {
+            v.push(i);
+        }
+        assert_eq!(v.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(v[i as usize], i);
+        }
+    }
+
+    #[test]
+    fn test_try_push_and_try_insert() {
+        let mut v: MyVec<i32> = MyVec::new();
+        assert_eq!(v.try_push(1), Ok(()));
+        assert_eq!(v.try_push(3), Ok(()));
+        assert_eq!(v.try_insert(1, 2), Ok(()));
+        assert_eq!(&*v, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_try_with_capacity() {
+        let v: MyVec<i32> = MyVec::try_with_capacity(10).unwrap();
+        assert_eq!(v.len(), 0);
+        assert!(v.capacity() >= 10);
+        assert_eq!(
+            MyVec::<i32>::try_with_capacity(usize::MAX).unwrap_err(),
+            TryReserveError::CapacityOverflow
+        );
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        assert_eq!(v.try_reserve(10), Ok(()));
+        assert!(v.capacity() >= 11);
+        assert_eq!(v.try_reserve_exact(20), Ok(()));
+        assert_eq!(v.capacity(), 21);
+        assert_eq!(
+            v.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            v.try_reserve_exact(isize::MAX as usize),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(&*v, &[1]);
+    }
+
+    #[test]
+    fn test_zst_try_reserve() {
+        let mut v: MyVec<()> = MyVec::new();
+        v.push(());
+        assert_eq!(v.try_reserve(usize::MAX - 1), Ok(()));
+        assert_eq!(
+            v.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        let alloc = CountingAlloc::new(usize::MAX);
+        {
+            let mut v = MyVec::new_in(&alloc);
+            for i in 0..100 {
+                v.push(i);
+            }
+            v.insert(0, -1);
+            assert_eq!(v.remove(0), -1);
+            assert_eq!(alloc.live.get(), 1);
+            for i in 0..100 {
+                assert_eq!(v[i as usize], i);
+            }
+        }
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn test_allocator_failure() {
+        let alloc = CountingAlloc::new(16);
+        let mut v: MyVec<u32, _> = MyVec::with_capacity_in(4, &alloc);
+        assert!(std::ptr::eq(*v.allocator(), &alloc));
+        for i in 0..4 {
+            assert_eq!(v.try_push(i), Ok(()));
+        }
+        assert_eq!(
+            v.try_push(4),
+            Err(TryReserveError::AllocError {
+                layout: Layout::array::<u32>(8).unwrap()
+            })
+        );
+        assert_eq!(&*v, &[0, 1, 2, 3]);
+        assert_eq!(v.try_swap_insert(1, 9), Err(9));
+        assert_eq!(&*v, &[0, 1, 2, 3]);
+        assert!(MyVec::<u32, _>::try_with_capacity_in(5, &alloc).is_err());
+    }
+
+    #[test]
+    fn test_into_iter_and_drain_in() {
+        let alloc = CountingAlloc::new(usize::MAX);
+        let mut v = MyVec::new_in(&alloc);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        {
+            let mut d = v.drain(..);
+            assert!(std::ptr::eq(*d.allocator(), &alloc));
+            assert_eq!(d.next(), Some(1));
+        }
+        assert_eq!(v.len(), 0);
+        v.push(4);
+        let mut it = v.into_iter();
+        assert!(std::ptr::eq(*it.allocator(), &alloc));
+        assert_eq!(alloc.live.get(), 1);
+        assert_eq!(it.next(), Some(4));
+        drop(it);
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn test_with_capacity_and_reserve() {
+        let mut v: MyVec<i32> = MyVec::with_capacity(10);
+        assert_eq!(v.capacity(), 10);
+        v.push(1);
+        v.reserve(10);
+        assert_eq!(v.capacity(), 20);
+        v.reserve_exact(30);
+        assert_eq!(v.capacity(), 31);
+        v.reserve(5);
+        assert_eq!(v.capacity(), 31);
+        assert_eq!(&*v, &[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_overflow() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        v.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut v: MyVec<i32> = MyVec::with_capacity(100);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.shrink_to(50);
+        assert_eq!(v.capacity(), 50);
+        v.shrink_to(60);
+        assert_eq!(v.capacity(), 50);
+        v.shrink_to(1);
+        assert_eq!(v.capacity(), 3);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 3);
+        assert_eq!(&*v, &[1, 2, 3]);
+        v.push(4);
+        assert_eq!(&*v, &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_shrink_to_fit_empty_releases_buffer() {
+        let alloc = CountingAlloc::new(usize::MAX);
+        let mut v: MyVec<i32, _> = MyVec::with_capacity_in(10, &alloc);
+        assert_eq!(alloc.live.get(), 1);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 0);
+        assert_eq!(alloc.live.get(), 0);
+        v.push(1);
+        assert_eq!(alloc.live.get(), 1);
+        assert_eq!(&*v, &[1]);
+    }
+
+    #[test]
+    fn test_zst_capacity() {
+        let mut v: MyVec<()> = MyVec::with_capacity(10);
+        assert_eq!(v.capacity(), usize::MAX);
+        v.push(());
+        v.reserve_exact(100);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), usize::MAX);
+        assert_eq!(v.len(), 1);
+    }
+
+    #[test]
+    fn test_default_growth() {
+        let mut v: MyVec<i32> = MyVec::new();
+        v.push(1);
+        assert_eq!(v.capacity(), 4);
+        for i in 0..4 {
+            v.push(i);
+        }
+        assert_eq!(v.capacity(), 8);
+        let mut bytes: MyVec<u8> = MyVec::new();
+        bytes.push(1);
+        assert_eq!(bytes.capacity(), 8);
+    }
+
+    #[test]
+    fn test_custom_growth() {
+        let mut v = MyVec::with_growth(FixedStep(3));
+        assert_eq!(v.growth_policy(), &FixedStep(3));
+        v.push(1);
+        assert_eq!(v.capacity(), 3);
+        for i in 2..=4 {
+            v.push(i);
+        }
+        assert_eq!(v.capacity(), 6);
+        v.reserve(10);
+        assert_eq!(v.capacity(), 14);
+        assert_eq!(&*v, &[1, 2, 3, 4]);
+
+        let alloc = CountingAlloc::new(usize::MAX);
+        let mut v = MyVec::with_growth_in(OneAndAHalf, &alloc);
+        for i in 0..10u64 {
+            v.push(i);
+        }
+        assert_eq!(v.capacity(), 13);
+        assert_eq!(v.into_iter().next_back(), Some(9));
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_write() {
+        use std::io::Write;
+        let mut v: MyVec<u8> = MyVec::new();
+        write!(v, "{}-ab", 12).unwrap();
+        v.write_all(b"!").unwrap();
+        assert_eq!(&*v, b"12-ab!");
+    }
+
+    #[test]
+    fn test_drain_range() {
+        let mut v: MyVec<i32> = MyVec::new();
+        for i in 0..6 {
+            v.push(i);
+        }
+        let mut it = v.drain(1..4);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next_back(), Some(3));
+        drop(it);
+        assert_eq!(&*v, &[0, 4, 5]);
+        assert_eq!(v.drain(..=0).collect::<std::vec::Vec<_>>(), [0]);
+        assert_eq!(&*v, &[4, 5]);
+        assert_eq!(v.drain(2..).count(), 0);
+        assert_eq!(&*v, &[4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end out of bounds")]
+    fn test_drain_range_out_of_bounds() {
         let mut v: MyVec<i32> = MyVec::new();
         v.push(1);
-        v.push(2);
-        v.push(3);
-        v[4] = 4;
+        v.drain(0..2);
     }
 
     #[test]
-    fn test_insert() {
+    #[should_panic(expected = "range start must not be greater than end")]
+    fn test_drain_range_inverted() {
         let mut v: MyVec<i32> = MyVec::new();
         v.push(1);
         v.push(2);
-        v.push(3);
-        v.insert(2, 9);
-        assert_eq!(v[3], 3);
-        assert_eq!(v[2], 9);
-        assert_eq!(v.len(), 4);
+        #[allow(clippy::reversed_empty_ranges)]
+        v.drain(2..1);
     }
 
     #[test]
-    fn test_remove_success() {
-        let mut v: MyVec<i32> = MyVec::new();
-        v.push(1);
-        v.push(2);
-        assert_eq!(v.remove(0), 1);
+    fn test_drain_forget_leaks_but_stays_valid() {
+        // counts drops instead of owning heap memory, so the leak doesn't trip Miri
+        struct Noisy<'a>(&'a Cell<usize>, char);
+
+        impl Drop for Noisy<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut v = MyVec::new();
+        for c in ['a', 'b', 'c', 'd'].iter() {
+            v.push(Noisy(&drops, *c));
+        }
+        std::mem::forget(v.drain(1..3));
         assert_eq!(v.len(), 1);
-        assert_eq!(v[0], 2);
+        v.push(Noisy(&drops, 'e'));
+        assert_eq!(v.iter().map(|n| n.1).collect::<std::string::String>(), "ae");
+        drop(v);
+        assert_eq!(drops.get(), 2);
     }
 
-    #[should_panic]
     #[test]
-    fn test_remove_fail() {
+    fn test_drain_keep_rest() {
         let mut v: MyVec<i32> = MyVec::new();
-        v.push(1);
-        v.push(2);
-        v.remove(2);
+        for i in 0..6 {
+            v.push(i);
+        }
+        let mut it = v.drain(1..5);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next_back(), Some(4));
+        it.keep_rest();
+        assert_eq!(&*v, &[0, 2, 3, 5]);
+
+        let mut z: MyVec<()> = MyVec::new();
+        for _ in 0..5 {
+            z.push(());
+        }
+        let mut it = z.drain(1..4);
+        it.next();
+        it.keep_rest();
+        assert_eq!(z.len(), 4);
     }
 
     #[test]
-    fn test_into_iter() {
-        let mut v: MyVec<i32> = MyVec::new();
-        v.push(1);
-        v.push(2);
-        v.push(3);
-        v.push(4);
-        v.push(5);
-        let mut it = v.into_iter();
-        assert_eq!(it.next(), Some(1));
-        assert_eq!(it.next(), Some(2));
-        assert_eq!(it.next_back(), Some(5));
-        assert_eq!(it.next_back(), Some(4));
-        assert_eq!(it.next(), Some(3));
-        assert_eq!(it.next(), None);
-        assert_eq!(it.next_back(), None);
+    fn test_splice() {
+        let mut v = my_vec![1, 2, 3, 4, 5];
+        let removed: std::vec::Vec<_> = v.splice(1..3, [7, 8].iter().cloned()).collect();
+        assert_eq!(removed, [2, 3]);
+        assert_eq!(&*v, &[1, 7, 8, 4, 5]);
+
+        v.splice(1..4, Some(9));
+        assert_eq!(&*v, &[1, 9, 5]);
+
+        v.splice(1..1, 10..15);
+        assert_eq!(&*v, &[1, 10, 11, 12, 13, 14, 9, 5]);
+
+        let mut splice = v.splice(6.., [20, 21, 22].iter().cloned());
+        assert_eq!(splice.len(), 2);
+        assert_eq!(splice.next_back(), Some(5));
+        assert_eq!(splice.size_hint(), (1, Some(1)));
+        drop(splice);
+        assert_eq!(&*v, &[1, 10, 11, 12, 13, 14, 20, 21, 22]);
+
+        v.splice(.., None);
+        assert!(v.is_empty());
     }
 
     #[test]
-    fn test_drain() {
-        let mut v: MyVec<i32> = MyVec::new();
-        v.push(1);
-        v.push(2);
-        v.push(3);
-        v.push(4);
-        v.push(5);
-        let mut it = v.drain();
-        assert_eq!(it.next(), Some(1));
-        assert_eq!(it.next(), Some(2));
-        assert_eq!(it.next_back(), Some(5));
-        assert_eq!(it.next_back(), Some(4));
-        assert_eq!(it.next(), Some(3));
-        assert_eq!(it.next(), None);
-        assert_eq!(it.next_back(), None);
+    fn test_splice_inexact_size_hint() {
+        let mut v = my_vec![1, 2, 3];
+        // filter reports a lower bound of 0, so everything goes through the fallback
+        v.splice(1..2, (10..20).filter(|i| i % 2 == 0));
+        assert_eq!(&*v, &[1, 10, 12, 14, 16, 18, 3]);
+
+        // a lower bound that's too low but non-zero
+        let mut v = my_vec![1, 2, 3];
+        v.splice(..1, (10..13).chain((20..30).filter(|i| i % 5 == 0)));
+        assert_eq!(&*v, &[10, 11, 12, 20, 25, 2, 3]);
     }
 
     #[test]
-    fn test_zst_drain() {
+    fn test_splice_zst() {
         let mut v: MyVec<()> = MyVec::new();
-        v.push(());
-        v.push(());
-        v.push(());
-        v.push(());
-        v.push(());
-        let mut it = v.drain();
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next_back(), Some(()));
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next_back(), Some(()));
-        assert_eq!(it.next(), None);
-        assert_eq!(it.next_back(), None);
+        for _ in 0..5 {
+            v.push(());
+        }
+        assert_eq!(v.splice(1..3, std::iter::repeat_n((), 4)).count(), 2);
+        assert_eq!(v.len(), 7);
     }
 
     #[test]
-    fn test_zst_iter() {
+    fn test_retain() {
+        let mut v = my_vec![1, 2, 3, 4, 5, 6];
+        v.retain(|&x| x % 2 == 0);
+        assert_eq!(&*v, &[2, 4, 6]);
+        v.retain_mut(|x| {
+            *x *= 10;
+            *x != 40
+        });
+        assert_eq!(&*v, &[20, 60]);
+        v.retain(|_| false);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_retain_panic_safety() {
+        let rc = Rc::new(());
+        let mut v: MyVec<Rc<()>> = MyVec::new();
+        for _ in 0..6 {
+            v.push(rc.clone());
+        }
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.retain(|_| {
+                calls += 1;
+                if calls == 4 {
+                    panic!("predicate panicked");
+                }
+                calls % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        // elements 1 and 3 were removed, the rest is kept
+        assert_eq!(v.len(), 4);
+        assert_eq!(Rc::strong_count(&rc), 5);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut v = my_vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let evens: std::vec::Vec<_> = v.extract_if(.., |x| *x % 2 == 0).collect();
+        assert_eq!(evens, [2, 4, 6, 8]);
+        assert_eq!(&*v, &[1, 3, 5, 7]);
+
+        let mut v = my_vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let extracted: std::vec::Vec<_> = v.extract_if(2..6, |x| *x > 1).collect();
+        assert_eq!(extracted, [3, 4, 5, 6]);
+        assert_eq!(&*v, &[1, 2, 7, 8]);
+    }
+
+    #[test]
+    fn test_extract_if_dropped_early_keeps_rest() {
+        let mut v = my_vec![1, 2, 3, 4, 5, 6];
+        let mut it = v.extract_if(.., |x| *x % 2 == 0);
+        assert_eq!(it.next(), Some(2));
+        drop(it);
+        assert_eq!(&*v, &[1, 3, 4, 5, 6]);
+
+        let mut z: MyVec<()> = MyVec::new();
+        for _ in 0..4 {
+            z.push(());
+        }
+        assert_eq!(z.extract_if(1.., |_| true).count(), 3);
+        assert_eq!(z.len(), 1);
+    }
+
+    #[test]
+    fn test_extract_if_panic_safety() {
+        let rc = Rc::new(());
+        let mut v: MyVec<Rc<()>> = MyVec::new();
+        for _ in 0..6 {
+            v.push(rc.clone());
+        }
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.extract_if(.., |_| {
+                calls += 1;
+                if calls == 3 {
+                    panic!("predicate panicked");
+                }
+                true
+            })
+            .for_each(drop)
+        }));
+        assert!(result.is_err());
+        assert_eq!(v.len(), 4);
+        assert_eq!(Rc::strong_count(&rc), 5);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut v = my_vec![1, 1, 2, 3, 3, 3, 1, 4, 4];
+        v.dedup();
+        assert_eq!(&*v, &[1, 2, 3, 1, 4]);
+
+        let mut v = my_vec![10, 11, 20, 21, 22, 30];
+        v.dedup_by_key(|x| *x / 10);
+        assert_eq!(&*v, &[10, 20, 30]);
+
+        let mut v = my_vec![1, 2, 4, 5, 7];
+        v.dedup_by(|cur, prev| *cur - *prev == 1);
+        assert_eq!(&*v, &[1, 4, 7]);
+
+        let mut v: MyVec<i32> = my_vec![];
+        v.dedup();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_panic_safety() {
+        let mut v: MyVec<Rc<i32>> = MyVec::new();
+        let items: std::vec::Vec<_> = [1, 1, 2, 2, 3, 3].iter().map(|&x| Rc::new(x)).collect();
+        for item in &items {
+            v.push(item.clone());
+        }
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.dedup_by(|a, b| {
+                calls += 1;
+                if calls == 3 {
+                    panic!("comparator panicked");
+                }
+                a == b
+            })
+        }));
+        assert!(result.is_err());
+        // the first duplicate was removed, everything from the panic on is kept
+        let values: std::vec::Vec<i32> = v.iter().map(|x| **x).collect();
+        assert_eq!(values, [1, 2, 2, 3, 3]);
+        assert_eq!(Rc::strong_count(&items[1]), 1);
+        drop(v);
+        assert!(items.iter().all(|x| Rc::strong_count(x) == 1));
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        let rc = Rc::new(());
+        let mut v: MyVec<Rc<()>> = MyVec::new();
+        for _ in 0..5 {
+            v.push(rc.clone());
+        }
+        let cap = v.capacity();
+        v.truncate(7);
+        assert_eq!(v.len(), 5);
+        v.truncate(2);
+        assert_eq!(v.len(), 2);
+        assert_eq!(Rc::strong_count(&rc), 3);
+        v.clear();
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), cap);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        let mut a = my_vec![1, 2, 3];
+        let mut b = my_vec![4, 5];
+        let b_cap = b.capacity();
+        a.append(&mut b);
+        assert_eq!(&*a, &[1, 2, 3, 4, 5]);
+        assert!(b.is_empty());
+        assert_eq!(b.capacity(), b_cap);
+
+        let c = a.split_off(2);
+        assert_eq!(&*a, &[1, 2]);
+        assert_eq!(&*c, &[3, 4, 5]);
+        let d = a.split_off(2);
+        assert!(d.is_empty());
+
+        let alloc = CountingAlloc::new(usize::MAX);
+        let mut v = MyVec::new_in(&alloc);
+        for i in 0..8 {
+            v.push(i);
+        }
+        let tail = v.split_off(3);
+        assert_eq!(&*tail, &[3, 4, 5, 6, 7]);
+        assert_eq!(alloc.live.get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_split_off_out_of_bounds() {
+        my_vec![1, 2].split_off(3);
+    }
+
+    #[test]
+    fn test_extend_from_slice_and_within() {
+        let mut v: MyVec<std::string::String> = MyVec::new();
+        let words = ["a", "b", "c"].map(std::string::String::from);
+        v.extend_from_slice(&words);
+        assert_eq!(v.capacity(), 4);
+        v.extend_from_within(1..);
+        v.extend_from_within(..1);
+        assert_eq!(&*v, &["a", "b", "c", "b", "c", "a"]);
+
+        let mut zst: MyVec<()> = MyVec::new();
+        zst.extend_from_slice(&[(), ()]);
+        zst.extend_from_within(..);
+        assert_eq!(zst.len(), 4);
+    }
+
+    #[test]
+    fn test_swap_remove_and_insert() {
+        let mut v = my_vec![1, 2, 3, 4];
+        assert_eq!(v.swap_remove(1), 2);
+        assert_eq!(&*v, &[1, 4, 3]);
+        assert_eq!(v.swap_remove(2), 3);
+        assert_eq!(&*v, &[1, 4]);
+        assert_eq!(v.try_swap_remove(2), None);
+
+        v.swap_insert(0, 7);
+        assert_eq!(&*v, &[7, 4, 1]);
+        v.swap_insert(3, 9);
+        assert_eq!(&*v, &[7, 4, 1, 9]);
+        assert_eq!(v.try_swap_insert(5, 0), Err(0));
+        assert_eq!(v.try_swap_insert(1, 0), Ok(()));
+        assert_eq!(&*v, &[7, 0, 1, 9, 4]);
+        assert_eq!(v.try_swap_remove(0), Some(7));
+        assert_eq!(&*v, &[4, 0, 1, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_swap_remove_out_of_bounds() {
+        my_vec![1].swap_remove(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_swap_insert_out_of_bounds() {
+        my_vec![1].swap_insert(2, 0);
+    }
+
+    #[test]
+    fn test_clone_and_clone_from() {
+        let v = my_vec![1, 2, 3];
+        let w = v.clone();
+        assert_eq!(v, w);
+        assert_ne!(v.as_ptr(), w.as_ptr());
+
+        let mut big = my_vec![9; 10];
+        let ptr = big.as_ptr();
+        big.clone_from(&v);
+        assert_eq!(big, v);
+        assert_eq!(big.as_ptr(), ptr);
+
+        let mut small = my_vec![7];
+        small.clone_from(&my_vec![1, 2, 3, 4, 5]);
+        assert_eq!(small, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let v = my_vec![1, 2, 3];
+        let slice: &[i32] = &[1, 2, 3];
+        assert_eq!(v, *slice);
+        assert_eq!(v, slice);
+        assert_eq!(v, [1, 2, 3]);
+        assert_eq!(v, &[1, 2, 3]);
+        assert_eq!(v, std::vec![1, 2, 3]);
+        assert_eq!(std::vec![1, 2, 3], v);
+        assert_eq!(*slice, v);
+        assert_eq!(slice, v);
+        assert_ne!(v, [1, 2]);
+
+        let other: MyVec<i32, Global, OneAndAHalf> = MyVec::with_growth(OneAndAHalf);
+        assert_ne!(v, other);
+        assert!(my_vec![1, 2] < my_vec![1, 3]);
+        assert!(my_vec![1, 2] < v);
+        assert_eq!(v.cmp(&v.clone()), core::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_hash_and_default() {
+        use core::hash::{Hash, Hasher};
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<H: Hash + ?Sized>(x: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let v = my_vec![1, 2, 3];
+        assert_eq!(hash_of(&v), hash_of(&[1, 2, 3][..]));
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(v.clone());
+        assert!(set.contains(&v));
+
+        let d: MyVec<i32, Global, Doubling> = Default::default();
+        assert!(d.is_empty());
+        assert_eq!(d.capacity(), 0);
+    }
+
+    #[test]
+    fn test_iterator_traits() {
+        let mut v: MyVec<i32> = (1..=4).collect();
+        assert_eq!(v, [1, 2, 3, 4]);
+        assert_eq!(v.capacity(), 4);
+
+        for x in &mut v {
+            *x *= 10;
+        }
+        let mut sum = 0;
+        for x in &v {
+            sum += *x;
+        }
+        assert_eq!(sum, 100);
+
+        v.extend(std::vec![50, 60]);
+        v.extend(&[70, 80]);
+        v.extend((0..100).filter(|x| *x == 90));
+        assert_eq!(v, [10, 20, 30, 40, 50, 60, 70, 80, 90]);
+
+        let mut seen = std::vec::Vec::new();
+        for x in v {
+            seen.push(x);
+        }
+        assert_eq!(seen.len(), 9);
+
+        let strings: MyVec<std::string::String, Global, Doubling> =
+            ["a", "b"].iter().map(|s| std::string::String::from(*s)).collect();
+        assert_eq!(strings, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_extend_with_lying_size_hint() {
+        struct Liar(u32, usize);
+
+        impl Iterator for Liar {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<u32> {
+                if self.0 == 10 {
+                    return None;
+                }
+                self.0 += 1;
+                Some(self.0)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.1, Some(self.1))
+            }
+        }
+
+        // claims fewer items than it has
+        let v: MyVec<u32> = Liar(0, 2).collect();
+        assert_eq!(v, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        // claims more items than it has
+        let v: MyVec<u32> = Liar(7, 50).collect();
+        assert_eq!(v, [8, 9, 10]);
+        assert_eq!(v.capacity(), 50);
+    }
+
+    #[test]
+    fn test_into_iter_traits() {
+        let mut iter = my_vec![1, 2, 3, 4].into_iter();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.as_slice(), &[2, 3, 4]);
+        iter.as_mut_slice()[0] = 20;
+        assert_eq!(std::format!("{:?}", iter), "IntoIter([20, 3, 4])");
+
+        let clone = iter.clone();
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(clone.as_slice(), &[20, 3, 4]);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(clone.collect::<std::vec::Vec<_>>(), [20, 3, 4]);
+    }
+
+    #[test]
+    fn test_drain_traits() {
+        let mut v = my_vec![1, 2, 3, 4, 5];
+        let mut drain = v.drain(1..4);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back(), Some(4));
+        assert_eq!(drain.size_hint(), (2, Some(2)));
+        drain.as_mut_slice()[1] = 30;
+        assert_eq!(drain.as_slice(), &[2, 30]);
+        assert_eq!(std::format!("{:?}", drain), "Drain([2, 30])");
+        drain.keep_rest();
+        assert_eq!(v, [1, 2, 30, 5]);
+    }
+
+    #[test]
+    fn test_iterator_traits_zst() {
         let mut v: MyVec<()> = MyVec::new();
-        v.push(());
-        v.push(());
-        v.push(());
-        v.push(());
-        v.push(());
-        let mut it = v.into_iter();
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next_back(), Some(()));
-        assert_eq!(it.next(), Some(()));
-        assert_eq!(it.next_back(), Some(()));
-        assert_eq!(it.next(), None);
-        assert_eq!(it.next_back(), None);
+        for _ in 0..5 {
+            v.push(());
+        }
+        let mut drain = v.drain(1..4);
+        assert_eq!(drain.len(), 3);
+        drain.next();
+        assert_eq!(drain.as_slice(), &[(), ()]);
+        drop(drain);
+        assert_eq!(v.len(), 2);
+
+        let mut iter = v.into_iter();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.as_mut_slice().len(), 2);
+        assert_eq!(std::format!("{:?}", iter.clone()), "IntoIter([(), ()])");
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+    }
+
+    #[test]
+    fn test_my_vec_macro() {
+        let v = my_vec![1, 2, 3,];
+        assert_eq!(v, [1, 2, 3]);
+        assert_eq!(v.capacity(), 3);
+        let empty: MyVec<u8> = my_vec![];
+        assert_eq!(empty.capacity(), 0);
+
+        let strings = my_vec![std::string::String::from("a"); 3];
+        assert_eq!(strings, ["a", "a", "a"]);
+        assert_eq!(strings.capacity(), 3);
+
+        let units = my_vec![(); 1000];
+        assert_eq!(units.len(), 1000);
+        assert_eq!(my_vec![(), ()].len(), 2);
+
+        // the last copy is the original, and a count of zero just drops it
+        let rc = Rc::new(());
+        let rcs = my_vec![rc.clone(); 3];
+        assert_eq!(Rc::strong_count(&rc), 4);
+        drop(rcs);
+        let none = my_vec![rc.clone(); 0];
+        assert!(none.is_empty());
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_vec_and_box_conversions() {
+        let mut std_vec = std::vec::Vec::with_capacity(10);
+        std_vec.extend_from_slice(&[1, 2, 3]);
+        let ptr = std_vec.as_ptr();
+        let v = MyVec::from(std_vec);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 10);
+        assert_eq!(v, [1, 2, 3]);
+
+        let back: std::vec::Vec<i32> = v.into();
+        assert_eq!(back.as_ptr(), ptr);
+        assert_eq!(back.capacity(), 10);
+
+        let boxed = MyVec::from(back).into_boxed_slice();
+        assert_eq!(&*boxed, &[1, 2, 3]);
+        let ptr = boxed.as_ptr();
+        let v = MyVec::from(boxed);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 3);
+
+        let empty: MyVec<i32> = MyVec::with_capacity(4);
+        assert!(empty.into_boxed_slice().is_empty());
+
+        let units: MyVec<()> = std::vec![(); 3].into();
+        assert_eq!(units.capacity(), usize::MAX);
+        let boxed = units.into_boxed_slice();
+        assert_eq!(boxed.len(), 3);
+        assert_eq!(std::vec::Vec::from(MyVec::from(boxed)).len(), 3);
+    }
+
+    #[test]
+    fn test_array_slice_and_raw_parts() {
+        let strings = MyVec::from([std::string::String::from("a"), std::string::String::from("b")]);
+        assert_eq!(strings, ["a", "b"]);
+        let copied = MyVec::from(&strings[..]);
+        assert_eq!(copied, strings);
+        assert_eq!(copied.capacity(), 2);
+
+        let mut v = my_vec![1, 2, 3];
+        v.reserve(5);
+        let (ptr, len, cap) = v.into_raw_parts();
+        assert_eq!(len, 3);
+        let mut v = unsafe { MyVec::from_raw_parts(ptr, len, cap) };
+        assert_eq!(v, [1, 2, 3]);
+        assert_eq!(v.capacity(), cap);
+
+        // the slice only covers `len` elements, so it can only give back a buffer that fits
+        v.shrink_to_fit();
+        let leaked: &'static mut [i32] = v.leak();
+        leaked[0] = 10;
+        assert_eq!(leaked, &[10, 2, 3]);
+        // give the buffer back so the test doesn't leak
+        drop(unsafe { MyVec::from_raw_parts(leaked.as_mut_ptr(), 3, 3) });
+    }
+
+    #[test]
+    fn test_spare_capacity() {
+        let mut v: MyVec<u32> = MyVec::with_capacity(8);
+        v.push(1);
+        assert_eq!(v.spare_capacity_mut().len(), 7);
+        for (i, slot) in v.spare_capacity_mut()[..3].iter_mut().enumerate() {
+            slot.write(i as u32 + 2);
+        }
+        unsafe { v.set_len(4) };
+        assert_eq!(v, [1, 2, 3, 4]);
+
+        let (init, spare) = v.split_at_spare_mut();
+        spare[0].write(init.iter().sum());
+        unsafe { v.set_len(5) };
+        assert_eq!(v, [1, 2, 3, 4, 10]);
+
+        unsafe { v.as_mut_ptr().add(5).write(6) };
+        unsafe { v.set_len(6) };
+        assert_eq!(unsafe { *v.as_ptr().add(5) }, 6);
+
+        let mut empty: MyVec<u64> = MyVec::new();
+        assert_eq!(empty.as_ptr() as usize % core::mem::align_of::<u64>(), 0);
+        assert!(empty.spare_capacity_mut().is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "new_len exceeds capacity")]
+    fn test_set_len_past_capacity() {
+        let mut v: MyVec<u32> = MyVec::with_capacity(2);
+        unsafe { v.set_len(3) };
+    }
+
+    /// How many elements the reallocation tests push. Kept small under Miri, where every push is
+    /// interpreted, but still enough for dozens of reallocations with `FixedStep`.
+    #[cfg(not(miri))]
+    const REALLOC_ROUNDS: usize = 2000;
+    #[cfg(miri)]
+    const REALLOC_ROUNDS: usize = 48;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct KiB([u64; 128]);
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(align(64))]
+    struct Align64(u64);
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(align(4096))]
+    struct PageAligned(u16);
+
+    /// Pushes `n` elements into `v` and checks every element, plus the buffer's alignment, after
+    /// each reallocation. Then shrinks to half and grows back. Returns how often the buffer moved
+    /// to a new capacity.
+    fn grow_and_check<T, A, G>(mut v: MyVec<T, A, G>, n: usize, make: impl Fn(usize) -> T) -> usize
+    where
+        T: Copy + PartialEq + core::fmt::Debug,
+        A: Allocator,
+        G: GrowthPolicy,
+    {
+        let check = |v: &MyVec<T, A, G>| {
+            assert_eq!(v.as_ptr() as usize % core::mem::align_of::<T>(), 0);
+            for (i, ele) in v.iter().enumerate() {
+                assert_eq!(*ele, make(i), "element {} of {}", i, v.len());
+            }
+        };
+        let mut reallocs = 0;
+        for i in 0..n {
+            let cap = v.capacity();
+            v.push(make(i));
+            if v.capacity() != cap {
+                reallocs += 1;
+                check(&v);
+            }
+        }
+        check(&v);
+
+        v.truncate(n / 2);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), n / 2);
+        check(&v);
+        for i in n / 2..n {
+            v.push(make(i));
+        }
+        check(&v);
+        reallocs
+    }
+
+    fn grow_with_policies<T>(n: usize, make: impl Fn(usize) -> T + Copy)
+    where
+        T: Copy + PartialEq + core::fmt::Debug,
+    {
+        grow_and_check(MyVec::new(), n, make);
+        grow_and_check(MyVec::with_growth(OneAndAHalf), n, make);
+        let reallocs = grow_and_check(MyVec::with_growth(FixedStep(3)), n, make);
+        assert_eq!(reallocs, n.div_ceil(3));
+
+        // an allocator without its own grow/shrink goes through allocate, copy and deallocate
+        let alloc = CountingAlloc::new(usize::MAX);
+        grow_and_check(MyVec::with_growth_in(FixedStep(3), &alloc), n, make);
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn test_realloc_primitives() {
+        grow_with_policies(REALLOC_ROUNDS, |i| i as u8);
+        grow_with_policies(REALLOC_ROUNDS, |i| (i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        grow_with_policies(REALLOC_ROUNDS, |i| ((i as u128) << 64) | !(i as u64) as u128);
+    }
+
+    #[test]
+    fn test_realloc_large_elements() {
+        grow_with_policies(REALLOC_ROUNDS / 4, |i| {
+            let mut words = [i as u64; 128];
+            words[127] = !(i as u64);
+            KiB(words)
+        });
+    }
+
+    #[test]
+    fn test_realloc_over_aligned() {
+        grow_with_policies(REALLOC_ROUNDS, |i| Align64(i as u64));
+        grow_with_policies(REALLOC_ROUNDS / 4, |i| PageAligned(i as u16));
     }
 }
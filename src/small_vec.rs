@@ -0,0 +1,402 @@
+use core::fmt;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut, Range, RangeBounds};
+use core::{ptr, slice};
+
+use crate::inline::{self, InlineStorage};
+use crate::{drop_elements, handle_reserve, slice_range, MyVec, RawVec, TryReserveError};
+
+/// A vector that keeps up to `N` elements inline and only moves them to a heap buffer once it
+/// outgrows that.
+pub struct SmallMyVec<T, const N: usize> {
+    len: usize,
+    data: Data<T, N>,
+}
+
+enum Data<T, const N: usize> {
+    Inline(MaybeUninit<[T; N]>),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> SmallMyVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            data: Data::Inline(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Returns whether the elements have been moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating. This is `N`
+    /// until the vector spills, and always `usize::MAX` for zero-sized types.
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) if mem::size_of::<T>() == 0 => usize::MAX,
+            Data::Inline(_) => N,
+            Data::Heap(buf) => buf.cap,
+        }
+    }
+
+    fn ptr(&self) -> *const T {
+        match &self.data {
+            Data::Inline(arr) => arr.as_ptr() as *const T,
+            Data::Heap(buf) => buf.ptr.as_ptr(),
+        }
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        match &mut self.data {
+            Data::Inline(arr) => arr.as_mut_ptr() as *mut T,
+            Data::Heap(buf) => buf.ptr.as_ptr(),
+        }
+    }
+
+    fn try_grow(&mut self) -> Result<(), TryReserveError> {
+        match &mut self.data {
+            Data::Heap(buf) => buf.try_grow(),
+            Data::Inline(arr) => {
+                // leave some headroom rather than reallocating on the very next push
+                let required = self
+                    .len
+                    .checked_add(1)
+                    .ok_or(TryReserveError::CapacityOverflow)?;
+                let mut buf = RawVec::new();
+                buf.try_reserve_exact(0, core::cmp::max(required, N.saturating_mul(2)))?;
+                unsafe {
+                    ptr::copy_nonoverlapping(arr.as_ptr() as *const T, buf.ptr.as_ptr(), self.len);
+                }
+                self.data = Data::Heap(buf);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn push(&mut self, ele: T) {
+        handle_reserve(self.try_push(ele))
+    }
+
+    /// Appends `ele`, returning an error instead of panicking or aborting if the vector needs to
+    /// spill or grow and can't. `ele` is dropped on failure.
+    pub fn try_push(&mut self, ele: T) -> Result<(), TryReserveError> {
+        if self.len == self.capacity() {
+            self.try_grow()?;
+        }
+        unsafe {
+            ptr::write(self.ptr_mut().add(self.len), ele);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(ptr::read(self.ptr().add(self.len))) }
+        }
+    }
+
+    pub fn insert(&mut self, idx: usize, ele: T) {
+        handle_reserve(self.try_insert(idx, ele))
+    }
+
+    /// Inserts `ele` at `idx`, returning an error instead of panicking or aborting if the vector
+    /// needs to spill or grow and can't. Still panics if `idx > len`.
+    pub fn try_insert(&mut self, idx: usize, ele: T) -> Result<(), TryReserveError> {
+        assert!(idx <= self.len, "index out of bounds");
+        if self.len == self.capacity() {
+            self.try_grow()?;
+        }
+        unsafe {
+            let ptr = self.ptr_mut().add(idx);
+            ptr::copy(ptr, ptr.add(1), self.len - idx);
+            ptr::write(ptr, ele);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, idx: usize) -> T {
+        assert!(idx < self.len, "index out of bounds");
+        unsafe {
+            let ptr = self.ptr_mut().add(idx);
+            let item = ptr.read();
+            ptr::copy(ptr.add(1), ptr, self.len - idx - 1);
+            self.len -= 1;
+            item
+        }
+    }
+
+    /// Removes the elements in `range`, yielding them from the returned iterator. Behaves
+    /// exactly like `MyVec::drain`.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        let Range { start, end } = slice_range(range, self.len);
+        let base = self.ptr_mut();
+        unsafe { Drain::new(base, &mut self.len, start, end) }
+    }
+
+    /// Converts into a `MyVec`, reusing the heap buffer if the vector has spilled and allocating
+    /// exactly `len` elements otherwise.
+    pub fn into_vec(self) -> MyVec<T> {
+        let mut this = ManuallyDrop::new(self);
+        let len = this.len;
+        match &mut this.data {
+            Data::Heap(buf) => MyVec {
+                buf: unsafe { ptr::read(buf) },
+                len,
+            },
+            Data::Inline(arr) => {
+                let mut vec = MyVec::with_capacity(len);
+                unsafe {
+                    ptr::copy_nonoverlapping(arr.as_ptr() as *const T, vec.ptr(), len);
+                }
+                vec.len = len;
+                vec
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallMyVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for SmallMyVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallMyVec<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.ptr_mut(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallMyVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { drop_elements(self.ptr_mut(), self.len) }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallMyVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallMyVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter::new(self)
+    }
+}
+
+impl<T, const N: usize> InlineStorage<T> for SmallMyVec<T, N> {
+    fn ptr(&self) -> *const T {
+        SmallMyVec::ptr(self)
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        SmallMyVec::ptr_mut(self)
+    }
+
+    fn take_len(&mut self) -> usize {
+        mem::replace(&mut self.len, 0)
+    }
+
+    fn from_cloned(items: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let mut v = SmallMyVec::new();
+        for item in items {
+            v.push(item.clone());
+        }
+        v
+    }
+}
+
+/// The iterator returned by `SmallMyVec::drain`.
+pub type Drain<'a, T, const N: usize> = inline::Drain<'a, T, SmallMyVec<T, N>>;
+
+/// The owning iterator of a `SmallMyVec`.
+pub type IntoIter<T, const N: usize> = inline::IntoIter<T, SmallMyVec<T, N>>;
+
+#[cfg(test)]
+mod tests {
+    use super::SmallMyVec;
+    use std::rc::Rc;
+
+    #[test]
+    fn stays_inline_until_full() {
+        let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+        for i in 0..4 {
+            v.push(i);
+        }
+        assert!(!v.spilled());
+        assert_eq!(v.capacity(), 4);
+        v.push(4);
+        assert!(v.spilled());
+        assert_eq!(v.capacity(), 8);
+        assert_eq!(&*v, &[0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn insert_remove_pop() {
+        let mut v: SmallMyVec<i32, 2> = SmallMyVec::new();
+        v.push(1);
+        v.push(3);
+        v.insert(1, 2);
+        assert!(v.spilled());
+        assert_eq!(&*v, &[1, 2, 3]);
+        assert_eq!(v.remove(0), 1);
+        v[0] = 5;
+        assert_eq!(v.pop(), Some(3));
+        assert_eq!(v.pop(), Some(5));
+        assert_eq!(v.pop(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_out_of_bounds() {
+        let mut v: SmallMyVec<i32, 2> = SmallMyVec::new();
+        v.push(1);
+        v.remove(1);
+    }
+
+    #[test]
+    fn drain_inline_and_spilled() {
+        for &count in &[3, 10] {
+            let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+            for i in 0..count {
+                v.push(i);
+            }
+            let mut it = v.drain(..);
+            assert_eq!(it.next(), Some(0));
+            assert_eq!(it.next_back(), Some(count - 1));
+            drop(it);
+            assert!(v.is_empty());
+        }
+    }
+
+    #[test]
+    fn drain_range_restores_tail() {
+        let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+        for i in 0..4 {
+            v.push(i);
+        }
+        assert_eq!(v.drain(1..3).next_back(), Some(2));
+        assert_eq!(&*v, &[0, 3]);
+        for i in 4..8 {
+            v.push(i);
+        }
+        let mut it = v.drain(..4);
+        assert_eq!(it.next(), Some(0));
+        it.keep_rest();
+        assert_eq!(&*v, &[3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn into_iter_inline_and_spilled() {
+        for &count in &[3, 10] {
+            let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+            for i in 0..count {
+                v.push(i);
+            }
+            let mut it = v.into_iter();
+            assert_eq!(it.next(), Some(0));
+            assert_eq!(it.next_back(), Some(count - 1));
+            assert_eq!(it.count(), count as usize - 2);
+        }
+    }
+
+    #[test]
+    fn iterator_traits_inline_and_spilled() {
+        for &count in &[4, 10] {
+            let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+            for i in 0..count {
+                v.push(i);
+            }
+            let mut drain = v.drain(1..);
+            assert_eq!(drain.len(), count as usize - 1);
+            assert_eq!(drain.next(), Some(1));
+            drain.as_mut_slice()[0] = 20;
+            assert_eq!(drain.as_slice()[0], 20);
+            drain.keep_rest();
+            assert_eq!(v[1], 20);
+
+            let mut iter = v.into_iter();
+            assert_eq!(iter.next(), Some(0));
+            assert_eq!(
+                iter.size_hint(),
+                (count as usize - 2, Some(count as usize - 2))
+            );
+            let clone = iter.clone();
+            assert_eq!(iter.next_back(), Some(count - 1));
+            assert_eq!(iter.by_ref().count(), count as usize - 3);
+            assert_eq!(iter.next(), None);
+            assert_eq!(clone.as_slice().first(), Some(&20));
+            assert_eq!(clone.count(), count as usize - 2);
+        }
+        let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+        v.push(1);
+        assert_eq!(std::format!("{:?}", v.drain(..)), "Drain([1])");
+    }
+
+    #[test]
+    fn into_vec() {
+        let mut v: SmallMyVec<i32, 4> = SmallMyVec::new();
+        v.push(1);
+        v.push(2);
+        let vec = v.into_vec();
+        assert_eq!(&*vec, &[1, 2]);
+        assert_eq!(vec.capacity(), 2);
+
+        let mut v: SmallMyVec<i32, 1> = SmallMyVec::new();
+        v.push(1);
+        v.push(2);
+        let cap = v.capacity();
+        let vec = v.into_vec();
+        assert_eq!(&*vec, &[1, 2]);
+        assert_eq!(vec.capacity(), cap);
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let rc = Rc::new(());
+        {
+            let mut v: SmallMyVec<Rc<()>, 2> = SmallMyVec::new();
+            for _ in 0..5 {
+                v.push(rc.clone());
+            }
+            v.remove(1);
+            let mut it = v.into_iter();
+            it.next();
+            assert_eq!(Rc::strong_count(&rc), 4);
+        }
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn zst_never_spills() {
+        let mut v: SmallMyVec<(), 2> = SmallMyVec::new();
+        for _ in 0..10 {
+            v.push(());
+        }
+        assert!(!v.spilled());
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.into_iter().count(), 10);
+    }
+}
@@ -0,0 +1,433 @@
+//! A model of `MyVec`: operations decoded from raw bytes, applied to both `MyVec` and
+//! `std::vec::Vec`, with the observable state compared after every step. Shared by the
+//! property tests and the fuzz targets.
+
+// every user of this module only needs part of it
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::fmt::Debug;
+
+use my_vec::MyVec;
+
+pub mod panicky;
+
+/// splitmix64, good enough to turn a seed into test cases and small enough to not need a crate.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+}
+
+/// Reads the fields of the operations out of a byte string, yielding zeros once it runs dry.
+struct Bytes<'a> {
+    data: &'a [u8],
+}
+
+impl Bytes<'_> {
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn u8(&mut self) -> u8 {
+        match self.data.split_first() {
+            Some((&b, rest)) => {
+                self.data = rest;
+                b
+            }
+            None => 0,
+        }
+    }
+
+    fn usize(&mut self) -> usize {
+        self.u8() as usize
+    }
+
+    fn bool(&mut self) -> bool {
+        self.u8() & 1 == 1
+    }
+}
+
+/// One step of a test case. Indices and lengths are taken modulo whatever is in bounds when the
+/// step runs, so every decoded sequence is valid.
+#[derive(Clone, Debug)]
+pub enum Op {
+    Push(u8),
+    Pop,
+    Insert(usize, u8),
+    Remove(usize),
+    SwapRemove(usize),
+    SwapInsert(usize, u8),
+    Truncate(usize),
+    Clear,
+    /// Drains a range, taking items from the front (bit set) or back (bit clear) of the drain
+    /// following the pattern, then either drops it or keeps the rest.
+    Drain {
+        start: usize,
+        end: usize,
+        take: usize,
+        pattern: u8,
+        keep_rest: bool,
+    },
+    /// Turns the vector into an iterator, consumes part of it from both ends and starts over
+    /// with an empty vector.
+    IntoIter {
+        take: usize,
+        pattern: u8,
+    },
+    Splice(usize, usize, Vec<u8>),
+    Retain(u8),
+    ExtractIf(usize, usize, u8),
+    Dedup,
+    ExtendFromSlice(Vec<u8>),
+    /// Extends from an iterator whose size hint is only a lower bound.
+    ExtendFiltered(Vec<u8>),
+    ExtendFromWithin(usize, usize),
+    SplitOffAndAppend(usize),
+    Reserve(usize),
+    ShrinkToFit,
+    Clone,
+}
+
+const OP_COUNT: u8 = 22;
+
+impl Op {
+    fn decode(b: &mut Bytes<'_>) -> Op {
+        match b.u8() % OP_COUNT {
+            0 => Op::Push(b.u8()),
+            1 => Op::Pop,
+            2 => Op::Insert(b.usize(), b.u8()),
+            3 => Op::Remove(b.usize()),
+            4 => Op::SwapRemove(b.usize()),
+            5 => Op::SwapInsert(b.usize(), b.u8()),
+            6 => Op::Truncate(b.usize()),
+            7 => Op::Clear,
+            8 => Op::Drain {
+                start: b.usize(),
+                end: b.usize(),
+                take: b.usize(),
+                pattern: b.u8(),
+                keep_rest: b.bool(),
+            },
+            9 => Op::IntoIter {
+                take: b.usize(),
+                pattern: b.u8(),
+            },
+            10 => Op::Splice(b.usize(), b.usize(), items(b)),
+            11 => Op::Retain(b.u8()),
+            12 => Op::ExtractIf(b.usize(), b.usize(), b.u8()),
+            13 => Op::Dedup,
+            14 => Op::ExtendFromSlice(items(b)),
+            15 => Op::ExtendFiltered(items(b)),
+            16 => Op::ExtendFromWithin(b.usize(), b.usize()),
+            17 => Op::SplitOffAndAppend(b.usize()),
+            18 => Op::Reserve(b.usize()),
+            19 => Op::ShrinkToFit,
+            20 => Op::Clone,
+            // pushes are the common case, give them a second slot
+            _ => Op::Push(b.u8()),
+        }
+    }
+}
+
+/// Up to 15 element values.
+fn items(b: &mut Bytes<'_>) -> Vec<u8> {
+    let len = b.usize() % 16;
+    (0..len).map(|_| b.u8()).collect()
+}
+
+pub fn decode_ops(data: &[u8]) -> Vec<Op> {
+    let mut b = Bytes { data };
+    let mut ops = Vec::new();
+    while !b.is_empty() {
+        ops.push(Op::decode(&mut b));
+    }
+    ops
+}
+
+thread_local! {
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+/// The number of `Counted` values alive on this thread.
+pub fn live_counted() -> isize {
+    LIVE.with(|live| live.get())
+}
+
+/// An element that keeps track of how many instances are alive, to catch leaks and double
+/// drops.
+#[derive(Debug, PartialEq)]
+pub struct Counted(u8);
+
+impl Counted {
+    fn new(value: u8) -> Self {
+        LIVE.with(|live| live.set(live.get() + 1));
+        Counted(value)
+    }
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        Counted::new(self.0)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        LIVE.with(|live| live.set(live.get() - 1));
+    }
+}
+
+/// An element type the model can be run with.
+pub trait Elem: Clone + PartialEq + Debug {
+    fn make(value: u8) -> Self;
+    /// The value predicates and dedup look at. Always 0 for zero-sized types.
+    fn value(&self) -> u8;
+}
+
+impl Elem for u32 {
+    fn make(value: u8) -> Self {
+        // spread the bits out so that misplaced bytes show up
+        u32::from(value) * 0x0101_0101
+    }
+
+    fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl Elem for () {
+    fn make(_: u8) -> Self {}
+
+    fn value(&self) -> u8 {
+        0
+    }
+}
+
+impl Elem for Counted {
+    fn make(value: u8) -> Self {
+        Counted::new(value)
+    }
+
+    fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+/// Values are folded down to a few buckets, so retain and dedup actually have work to do.
+fn keep(value: u8, filter: u8) -> bool {
+    (value % 4) != (filter % 4)
+}
+
+fn check<T: Elem>(mine: &MyVec<T>, model: &[T]) {
+    assert_eq!(&mine[..], model);
+    assert!(mine.capacity() >= mine.len());
+}
+
+/// Picks an in-bounds range out of two raw values.
+fn range(a: usize, b: usize, len: usize) -> (usize, usize) {
+    let a = a % (len + 1);
+    let b = b % (len + 1);
+    (a.min(b), a.max(b))
+}
+
+/// Pulls `take` items off both iterators, from the front or back as `pattern` says, and checks
+/// they agree.
+fn take_both<T, I, J>(mine: &mut I, model: &mut J, take: usize, pattern: u8) -> (usize, usize)
+where
+    T: Elem,
+    I: DoubleEndedIterator<Item = T> + ExactSizeIterator,
+    J: DoubleEndedIterator<Item = T> + ExactSizeIterator,
+{
+    let (mut front, mut back) = (0, 0);
+    for step in 0..take {
+        assert_eq!(mine.len(), model.len());
+        let from_front = pattern.rotate_left(step as u32) & 1 == 1;
+        let (a, b) = if from_front {
+            (mine.next(), model.next())
+        } else {
+            (mine.next_back(), model.next_back())
+        };
+        assert_eq!(a, b);
+        if a.is_none() {
+            break;
+        }
+        if from_front {
+            front += 1;
+        } else {
+            back += 1;
+        }
+    }
+    (front, back)
+}
+
+/// Runs `ops` against `MyVec<T>` and `Vec<T>`, panicking at the first difference.
+pub fn run<T: Elem>(ops: &[Op]) {
+    let mut mine: MyVec<T> = MyVec::new();
+    let mut model: Vec<T> = Vec::new();
+
+    for op in ops {
+        let len = model.len();
+        match op.clone() {
+            Op::Push(v) => {
+                mine.push(T::make(v));
+                model.push(T::make(v));
+            }
+            Op::Pop => assert_eq!(mine.pop(), model.pop()),
+            Op::Insert(idx, v) => {
+                let idx = idx % (len + 1);
+                mine.insert(idx, T::make(v));
+                model.insert(idx, T::make(v));
+            }
+            Op::Remove(idx) if len > 0 => {
+                assert_eq!(mine.remove(idx % len), model.remove(idx % len))
+            }
+            Op::SwapRemove(idx) if len > 0 => {
+                assert_eq!(mine.swap_remove(idx % len), model.swap_remove(idx % len))
+            }
+            Op::Remove(_) | Op::SwapRemove(_) => {
+                assert_eq!(mine.try_swap_remove(0), None);
+            }
+            Op::SwapInsert(idx, v) => {
+                let idx = idx % (len + 1);
+                mine.swap_insert(idx, T::make(v));
+                model.push(T::make(v));
+                model.swap(idx, len);
+            }
+            Op::Truncate(n) => {
+                mine.truncate(n % (len + 2));
+                model.truncate(n % (len + 2));
+            }
+            Op::Clear => {
+                mine.clear();
+                model.clear();
+            }
+            Op::Drain {
+                start,
+                end,
+                take,
+                pattern,
+                keep_rest,
+            } => {
+                let (start, end) = range(start, end, len);
+                // Vec can't keep the rest of a drain, so that case is replayed on a copy
+                let before = model.clone();
+                let mut drain = mine.drain(start..end);
+                let mut model_drain = model.drain(start..end);
+                let (front, back) = take_both(&mut drain, &mut model_drain, take % 8, pattern);
+                assert_eq!(drain.as_slice(), model_drain.as_slice());
+                drop(model_drain);
+                if keep_rest {
+                    drain.keep_rest();
+                    model = before;
+                    model.drain(end - back..end);
+                    model.drain(start..start + front);
+                } else {
+                    drop(drain);
+                }
+            }
+            Op::IntoIter { take, pattern } => {
+                let mut iter = std::mem::take(&mut mine).into_iter();
+                let mut model_iter = std::mem::take(&mut model).into_iter();
+                take_both(&mut iter, &mut model_iter, take % 16, pattern);
+                assert_eq!(iter.as_slice(), model_iter.as_slice());
+                assert_eq!(iter.clone().collect::<Vec<_>>(), model_iter.as_slice());
+            }
+            Op::Splice(start, end, items) => {
+                let (start, end) = range(start, end, len);
+                let removed: Vec<T> = mine
+                    .splice(start..end, items.iter().map(|&v| T::make(v)))
+                    .collect();
+                let model_removed: Vec<T> = model
+                    .splice(start..end, items.iter().map(|&v| T::make(v)))
+                    .collect();
+                assert_eq!(removed, model_removed);
+            }
+            Op::Retain(filter) => {
+                mine.retain(|x| keep(x.value(), filter));
+                model.retain(|x| keep(x.value(), filter));
+            }
+            Op::ExtractIf(start, end, filter) => {
+                let (start, end) = range(start, end, len);
+                let out: Vec<T> = mine
+                    .extract_if(start..end, |x| !keep(x.value(), filter))
+                    .collect();
+                let model_out: Vec<T> = model
+                    .extract_if(start..end, |x| !keep(x.value(), filter))
+                    .collect();
+                assert_eq!(out, model_out);
+            }
+            Op::Dedup => {
+                mine.dedup_by_key(|x| x.value() % 4);
+                model.dedup_by_key(|x| x.value() % 4);
+            }
+            Op::ExtendFromSlice(items) => {
+                let items: Vec<T> = items.into_iter().map(T::make).collect();
+                mine.extend_from_slice(&items);
+                model.extend_from_slice(&items);
+            }
+            Op::ExtendFiltered(items) => {
+                mine.extend(items.iter().filter(|&&v| v % 3 != 0).map(|&v| T::make(v)));
+                model.extend(items.iter().filter(|&&v| v % 3 != 0).map(|&v| T::make(v)));
+            }
+            Op::ExtendFromWithin(start, end) => {
+                let (start, end) = range(start, end, len);
+                mine.extend_from_within(start..end);
+                model.extend_from_within(start..end);
+            }
+            Op::SplitOffAndAppend(at) => {
+                let at = at % (len + 1);
+                let mut tail = mine.split_off(at);
+                let model_tail = model.split_off(at);
+                check(&tail, &model_tail);
+                check(&mine, &model);
+                mine.append(&mut tail);
+                assert!(tail.is_empty());
+                model.extend(model_tail);
+            }
+            Op::Reserve(additional) => {
+                mine.reserve(additional);
+                assert!(mine.capacity() >= len + additional);
+            }
+            Op::ShrinkToFit => {
+                mine.shrink_to_fit();
+                if std::mem::size_of::<T>() != 0 {
+                    assert_eq!(mine.capacity(), len);
+                }
+            }
+            Op::Clone => {
+                let copy = mine.clone();
+                check(&copy, &model);
+                mine.clone_from(&copy);
+            }
+        }
+        check(&mine, &model);
+    }
+}
+
+/// Runs `ops` with plain, zero-sized and drop-counting elements.
+pub fn run_all(ops: &[Op]) {
+    run::<u32>(ops);
+    run::<()>(ops);
+    let before = live_counted();
+    run::<Counted>(ops);
+    assert_eq!(
+        live_counted(),
+        before,
+        "Counted values leaked or dropped twice"
+    );
+}
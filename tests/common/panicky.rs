@@ -0,0 +1,215 @@
+//! Runs operation sequences on a `MyVec` of elements that sometimes panic when dropped or cloned.
+//! There's no model to compare against once panics are involved, so this checks the invariants
+//! that must survive any panic instead: `len <= capacity`, every element in the vector is alive,
+//! nothing is dropped twice, and nothing leaks unless something panicked.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+
+use my_vec::MyVec;
+
+use super::{Elem, Op};
+
+thread_local! {
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    static ALIVE: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+    static DOUBLE_DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// An element that panics when dropped or cloned if its value says so, and keeps track of
+/// which instances are alive.
+#[derive(Debug)]
+pub struct Bomb {
+    id: u64,
+    value: u8,
+}
+
+impl Bomb {
+    fn new(value: u8) -> Self {
+        let id = NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        });
+        ALIVE.with(|alive| alive.borrow_mut().insert(id));
+        Bomb { id, value }
+    }
+
+    fn panics_on_drop(&self) -> bool {
+        self.value % 32 == 31
+    }
+
+    fn panics_on_clone(&self) -> bool {
+        self.value % 32 == 30
+    }
+}
+
+impl PartialEq for Bomb {
+    fn eq(&self, other: &Bomb) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Clone for Bomb {
+    fn clone(&self) -> Self {
+        if self.panics_on_clone() {
+            panic!("clone of bomb {} panicked", self.id);
+        }
+        Bomb::new(self.value)
+    }
+}
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        if !ALIVE.with(|alive| alive.borrow_mut().remove(&self.id)) {
+            DOUBLE_DROPS.with(|count| count.set(count.get() + 1));
+        }
+        // a second panic while unwinding would abort the whole run
+        if self.panics_on_drop() && !std::thread::panicking() {
+            panic!("drop of bomb {} panicked", self.id);
+        }
+    }
+}
+
+impl Elem for Bomb {
+    fn make(value: u8) -> Self {
+        Bomb::new(value)
+    }
+
+    fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+/// The number of `Bomb`s alive on this thread.
+pub fn alive_count() -> usize {
+    ALIVE.with(|alive| alive.borrow().len())
+}
+
+/// How often a `Bomb` was dropped a second time on this thread.
+pub fn double_drops() -> usize {
+    DOUBLE_DROPS.with(Cell::get)
+}
+
+/// Applies `op` to `v` alone, mirroring what `super::run` does to both vectors.
+fn apply(v: &mut MyVec<Bomb>, op: &Op) {
+    let len = v.len();
+    let range = |a: usize, b: usize| super::range(a, b, len);
+    match op.clone() {
+        Op::Push(x) => v.push(Bomb::new(x)),
+        Op::Pop => drop(v.pop()),
+        Op::Insert(idx, x) => v.insert(idx % (len + 1), Bomb::new(x)),
+        Op::Remove(idx) if len > 0 => drop(v.remove(idx % len)),
+        Op::SwapRemove(idx) if len > 0 => drop(v.swap_remove(idx % len)),
+        Op::Remove(_) | Op::SwapRemove(_) => {}
+        Op::SwapInsert(idx, x) => v.swap_insert(idx % (len + 1), Bomb::new(x)),
+        Op::Truncate(n) => v.truncate(n % (len + 2)),
+        Op::Clear => v.clear(),
+        Op::Drain {
+            start,
+            end,
+            take,
+            pattern,
+            keep_rest,
+        } => {
+            let (start, end) = range(start, end);
+            let mut drain = v.drain(start..end);
+            for step in 0..take % 8 {
+                if pattern.rotate_left(step as u32) & 1 == 1 {
+                    drop(drain.next());
+                } else {
+                    drop(drain.next_back());
+                }
+            }
+            if keep_rest {
+                drain.keep_rest();
+            }
+        }
+        Op::IntoIter { take, pattern } => {
+            let mut iter = std::mem::take(v).into_iter();
+            for step in 0..take % 16 {
+                if pattern.rotate_left(step as u32) & 1 == 1 {
+                    drop(iter.next());
+                } else {
+                    drop(iter.next_back());
+                }
+            }
+            drop(iter.clone());
+        }
+        Op::Splice(start, end, items) => {
+            let (start, end) = range(start, end);
+            v.splice(start..end, items.into_iter().map(Bomb::new))
+                .for_each(drop);
+        }
+        Op::Retain(filter) => v.retain(|x| super::keep(x.value, filter)),
+        Op::ExtractIf(start, end, filter) => {
+            let (start, end) = range(start, end);
+            v.extract_if(start..end, |x| !super::keep(x.value, filter))
+                .for_each(drop);
+        }
+        Op::Dedup => v.dedup_by_key(|x| x.value % 4),
+        Op::ExtendFromSlice(items) => {
+            let items: Vec<Bomb> = items.into_iter().map(Bomb::new).collect();
+            v.extend_from_slice(&items);
+        }
+        Op::ExtendFiltered(items) => {
+            v.extend(items.into_iter().filter(|x| x % 3 != 0).map(Bomb::new));
+        }
+        Op::ExtendFromWithin(start, end) => {
+            let (start, end) = range(start, end);
+            v.extend_from_within(start..end);
+        }
+        Op::SplitOffAndAppend(at) => {
+            let mut tail = v.split_off(at % (len + 1));
+            v.append(&mut tail);
+        }
+        Op::Reserve(additional) => v.reserve(additional),
+        Op::ShrinkToFit => v.shrink_to_fit(),
+        Op::Clone => {
+            let copy = v.clone();
+            v.clone_from(&copy);
+        }
+    }
+}
+
+fn check(v: &MyVec<Bomb>, panicked: bool) {
+    assert!(v.len() <= v.capacity());
+    assert_eq!(double_drops(), 0, "an element was dropped twice");
+    ALIVE.with(|alive| {
+        let alive = alive.borrow();
+        let mut seen = HashSet::new();
+        for bomb in v.iter() {
+            assert!(alive.contains(&bomb.id), "bomb {} is dead", bomb.id);
+            assert!(
+                seen.insert(bomb.id),
+                "bomb {} is in the vector twice",
+                bomb.id
+            );
+        }
+    });
+    if !panicked {
+        assert_eq!(alive_count(), v.len(), "elements leaked without a panic");
+    }
+}
+
+/// Runs `ops` on a `MyVec<Bomb>`, catching every panic and checking the invariants after each
+/// step. The panics still go through the panic hook, callers that run lots of cases may want to
+/// silence it.
+pub fn run_panicky(ops: &[Op]) {
+    ALIVE.with(|alive| alive.borrow_mut().clear());
+    DOUBLE_DROPS.with(|count| count.set(0));
+    let mut v: MyVec<Bomb> = MyVec::new();
+    let mut panicked = false;
+
+    for op in ops {
+        panicked |= panic::catch_unwind(AssertUnwindSafe(|| apply(&mut v, op))).is_err();
+        check(&v, panicked);
+    }
+    panicked |= panic::catch_unwind(AssertUnwindSafe(|| drop(v))).is_err();
+
+    assert_eq!(double_drops(), 0, "an element was dropped twice");
+    if !panicked {
+        assert_eq!(alive_count(), 0, "elements leaked without a panic");
+    }
+}
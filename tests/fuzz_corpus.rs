@@ -0,0 +1,48 @@
+//! Replays the fuzzing corpus, so the cases found by the fuzzer keep running under plain
+//! `cargo test`. Crashes saved under `fuzz/artifacts` are replayed too.
+
+mod common;
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use common::panicky::run_panicky;
+use common::{decode_ops, run_all};
+
+fn replay(target: &str, run: fn(&[u8])) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz");
+    let mut cases = 0;
+    for dir in &["corpus", "artifacts"] {
+        let entries = match fs::read_dir(root.join(dir).join(target)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries {
+            let path = entry.unwrap().path();
+            let data = fs::read(&path).unwrap();
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| run(&data))) {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("non-string panic payload");
+                panic!("replaying {} failed: {}", path.display(), message);
+            }
+            cases += 1;
+        }
+    }
+    assert!(cases > 0, "no corpus for the {} target", target);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // reads the file system
+fn replay_ops_corpus() {
+    replay("ops", |data| run_all(&decode_ops(data)));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn replay_panics_corpus() {
+    replay("panics", |data| run_panicky(&decode_ops(data)));
+}
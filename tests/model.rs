@@ -0,0 +1,63 @@
+//! Differential tests: random operation sequences run against both `MyVec` and `Vec`.
+
+mod common;
+
+use common::panicky::run_panicky;
+use common::{decode_ops, run_all, Op, Rng};
+
+#[cfg(not(miri))]
+const CASES: u64 = 500;
+#[cfg(miri)]
+const CASES: u64 = 8;
+
+#[test]
+fn random_sequences_match_vec() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let len = (rng.next_u64() % 512) as usize;
+        let ops = decode_ops(&rng.bytes(len));
+        run_all(&ops);
+    }
+}
+
+#[test]
+fn random_sequences_with_panicking_elements() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(!seed);
+        let len = (rng.next_u64() % 512) as usize;
+        run_panicky(&decode_ops(&rng.bytes(len)));
+    }
+}
+
+#[test]
+fn long_push_heavy_sequence() {
+    let mut ops = Vec::new();
+    for i in 0..200u8 {
+        ops.push(Op::Push(i));
+        if i % 7 == 0 {
+            ops.push(Op::Remove(i as usize));
+        }
+        if i % 31 == 0 {
+            ops.push(Op::Drain {
+                start: i as usize / 2,
+                end: i as usize,
+                take: 3,
+                pattern: 0b1010,
+                keep_rest: i % 2 == 0,
+            });
+        }
+    }
+    ops.push(Op::IntoIter {
+        take: 9,
+        pattern: 0b0110_1101,
+    });
+    run_all(&ops);
+}
+
+#[test]
+fn every_op_on_an_empty_vector() {
+    // one of each tag, followed by nothing but zeros
+    for tag in 0..=255u8 {
+        run_all(&decode_ops(&[tag]));
+    }
+}
@@ -0,0 +1,286 @@
+//! Drop accounting and panic safety for every method that drops, clones or moves elements, on
+//! `MyVec` as well as the inline vectors. `Counted` catches leaks and double drops, and `Bomb`
+//! panics when dropped (value 31) or cloned (value 30).
+
+mod common;
+
+use std::iter::FromIterator;
+use std::panic::{self, AssertUnwindSafe};
+
+use my_vec::{my_vec, ArrayMyVec, MyVec, SmallMyVec};
+
+use common::panicky::{alive_count, double_drops, Bomb};
+use common::{live_counted, Counted, Elem};
+
+const DROP_BOMB: u8 = 31;
+const CLONE_BOMB: u8 = 30;
+
+fn catch<R>(f: impl FnOnce() -> R) -> std::thread::Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+fn counted(n: usize) -> MyVec<Counted> {
+    (0..n).map(|i| Counted::make(i as u8)).collect()
+}
+
+/// `len` bombs, of which the one at `armed_at` panics when dropped.
+fn bombs<V: FromIterator<Bomb>>(len: usize, armed_at: usize) -> V {
+    (0..len)
+        .map(|i| Bomb::make(if i == armed_at { DROP_BOMB } else { i as u8 }))
+        .collect()
+}
+
+/// Checks that exactly `len` bombs are alive and none was dropped twice.
+fn assert_alive(len: usize) {
+    assert_eq!(double_drops(), 0, "a bomb was dropped twice");
+    assert_eq!(alive_count(), len, "bombs leaked or were dropped early");
+}
+
+/// Runs `f` on a vector of 8 `Counted`s and returns the length it's left with, checking that
+/// the elements still alive are exactly the ones in the vector and that dropping it drops them.
+fn len_after<F: FnOnce(&mut MyVec<Counted>)>(f: F) -> usize {
+    let base = live_counted();
+    let mut v = counted(8);
+    f(&mut v);
+    let len = v.len();
+    assert_eq!(
+        live_counted() - base,
+        len as isize,
+        "elements leaked or dropped twice"
+    );
+    drop(v);
+    assert_eq!(live_counted(), base, "elements leaked or dropped twice");
+    len
+}
+
+#[test]
+fn drop_accounting_every_method() {
+    assert_eq!(len_after(|v| drop(v.pop())), 7);
+    assert_eq!(len_after(|v| v.push(Counted::make(0))), 9);
+    assert_eq!(len_after(|v| v.insert(3, Counted::make(0))), 9);
+    assert_eq!(len_after(|v| drop(v.remove(3))), 7);
+    assert_eq!(len_after(|v| drop(v.swap_remove(3))), 7);
+    assert_eq!(len_after(|v| v.swap_insert(3, Counted::make(0))), 9);
+    assert_eq!(len_after(|v| v.truncate(5)), 5);
+    assert_eq!(len_after(|v| v.clear()), 0);
+    assert_eq!(len_after(|v| drop(v.drain(2..6))), 4);
+    assert_eq!(len_after(|v| drop(v.drain(2..6).next())), 4);
+    assert_eq!(len_after(|v| v.drain(2..6).keep_rest()), 8);
+    assert_eq!(len_after(|v| drop(v.splice(2..6, counted(3)))), 7);
+    assert_eq!(
+        len_after(|v| v.extract_if(.., |_| true).take(2).for_each(drop)),
+        6
+    );
+    let mut flip = false;
+    let alternate = |_: &Counted| {
+        flip = !flip;
+        flip
+    };
+    assert_eq!(len_after(|v| v.retain(alternate)), 4);
+    assert_eq!(len_after(|v| v.dedup_by(|_, _| true)), 1);
+    assert_eq!(len_after(|v| v.extend_from_slice(&counted(2))), 10);
+    assert_eq!(len_after(|v| v.extend_from_within(..4)), 12);
+    assert_eq!(len_after(|v| v.extend(counted(3))), 11);
+    assert_eq!(len_after(|v| drop(v.split_off(2))), 2);
+    assert_eq!(len_after(|v| v.append(&mut counted(2))), 10);
+    assert_eq!(len_after(|v| drop(v.clone())), 8);
+    assert_eq!(len_after(|v| v.clone_from(&counted(3))), 3);
+    assert_eq!(len_after(|v| v.shrink_to_fit()), 8);
+    assert_eq!(
+        len_after(|v| drop(std::mem::take(v).into_iter().rev().take(3))),
+        0
+    );
+}
+
+#[test]
+fn drop_accounting_inline_vectors() {
+    let base = live_counted();
+    let mut array: ArrayMyVec<Counted, 8> = ArrayMyVec::new();
+    for i in 0..8 {
+        array.push(Counted::make(i)).unwrap();
+    }
+    assert!(array.push(Counted::make(8)).is_err());
+    drop(array.pop());
+    drop(array.remove(0));
+    assert!(array.insert(0, Counted::make(0)).is_ok());
+    drop(array.drain(1..3).next());
+    array.drain(..2).keep_rest();
+    assert_eq!(live_counted() - base, array.len() as isize);
+    drop(array.into_iter().skip(1).take(1));
+    assert_eq!(live_counted(), base);
+
+    // four elements fit inline, the ninth spills
+    for &len in &[4u8, 9] {
+        let mut small: SmallMyVec<Counted, 4> = SmallMyVec::new();
+        for i in 0..len {
+            small.push(Counted::make(i));
+        }
+        drop(small.pop());
+        drop(small.remove(0));
+        small.insert(1, Counted::make(0));
+        drop(small.drain(1..2).next_back());
+        assert_eq!(live_counted() - base, small.len() as isize);
+        let mut iter = small.into_iter();
+        drop(iter.next());
+        drop(iter.clone());
+        drop(iter);
+        assert_eq!(live_counted(), base);
+    }
+}
+
+#[test]
+fn panicking_drop_in_vec_drop_drops_the_rest() {
+    let v: MyVec<Bomb> = bombs(6, 2);
+    assert!(catch(|| drop(v)).is_err());
+    assert_alive(0);
+}
+
+#[test]
+fn panicking_drop_in_truncate_and_clear() {
+    let mut v: MyVec<Bomb> = bombs(6, 3);
+    assert!(catch(|| v.truncate(2)).is_err());
+    assert_eq!(v.len(), 2);
+    assert_alive(2);
+    drop(v);
+
+    let mut v: MyVec<Bomb> = bombs(6, 0);
+    assert!(catch(|| v.clear()).is_err());
+    assert!(v.is_empty());
+    assert_alive(0);
+}
+
+#[test]
+fn panicking_drop_in_into_iter() {
+    let mut iter = bombs::<MyVec<Bomb>>(6, 4).into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert!(catch(|| drop(iter)).is_err());
+    assert_alive(0);
+}
+
+#[test]
+fn panicking_drop_in_drain() {
+    let mut v: MyVec<Bomb> = bombs(8, 3);
+    let mut drain = v.drain(2..6);
+    drop(drain.next());
+    assert!(catch(|| drop(drain)).is_err());
+    // the whole range is gone and the tail is back in place
+    assert_eq!(v.len(), 4);
+    assert_alive(4);
+    drop(v);
+    assert_alive(0);
+}
+
+#[test]
+fn panicking_drop_in_splice_and_removals() {
+    let mut v: MyVec<Bomb> = bombs(8, 3);
+    assert!(catch(|| drop(v.splice(2..6, std::iter::empty()))).is_err());
+    assert_eq!(v.len(), 4);
+    drop(v);
+    assert_alive(0);
+
+    for armed_at in 0..4 {
+        let mut v: MyVec<Bomb> = bombs(4, armed_at);
+        // the first element is never a duplicate, so it only goes off with the vector
+        assert_eq!(catch(|| v.dedup_by(|_, _| true)).is_err(), armed_at != 0);
+        assert_eq!(catch(|| drop(v)).is_err(), armed_at == 0);
+        assert_alive(0);
+
+        let mut v: MyVec<Bomb> = bombs(4, armed_at);
+        assert!(catch(|| v.retain(|_| false)).is_err());
+        drop(v);
+        assert_alive(0);
+
+        let mut v: MyVec<Bomb> = bombs(4, armed_at);
+        assert!(catch(|| drop(v.remove(armed_at))).is_err());
+        assert_eq!(v.len(), 3);
+        drop(v);
+        assert_alive(0);
+    }
+}
+
+#[test]
+fn panicking_drop_in_array_vec() {
+    let mut v: ArrayMyVec<Bomb, 8> = ArrayMyVec::new();
+    for bomb in bombs::<Vec<Bomb>>(8, 3) {
+        v.push(bomb).unwrap();
+    }
+    let mut drain = v.drain(2..6);
+    drop(drain.next());
+    assert!(catch(|| drop(drain)).is_err());
+    assert_eq!(v.len(), 4);
+    assert_alive(4);
+    v.push(Bomb::make(DROP_BOMB)).unwrap();
+    assert!(catch(|| drop(v)).is_err());
+    assert_alive(0);
+
+    let mut v: ArrayMyVec<Bomb, 8> = ArrayMyVec::new();
+    for bomb in bombs::<Vec<Bomb>>(6, 4) {
+        v.push(bomb).unwrap();
+    }
+    let mut iter = v.into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert!(catch(|| drop(iter)).is_err());
+    assert_alive(0);
+}
+
+fn small_bombs(len: usize, armed_at: usize) -> SmallMyVec<Bomb, 4> {
+    let mut v = SmallMyVec::new();
+    for bomb in bombs::<Vec<Bomb>>(len, armed_at) {
+        v.push(bomb);
+    }
+    v
+}
+
+#[test]
+fn panicking_drop_in_small_vec() {
+    // four elements fit inline, eight spill
+    for &len in &[4, 8] {
+        let mut v = small_bombs(len, 2);
+        let mut drain = v.drain(1..3);
+        drop(drain.next());
+        assert!(catch(|| drop(drain)).is_err());
+        assert_eq!(v.len(), len - 2);
+        assert_alive(len - 2);
+        v.push(Bomb::make(DROP_BOMB));
+        assert!(catch(|| drop(v)).is_err());
+        assert_alive(0);
+
+        let v = small_bombs(len, len - 2);
+        let mut iter = v.into_iter();
+        drop(iter.next());
+        drop(iter.next_back());
+        assert!(catch(|| drop(iter)).is_err());
+        assert_alive(0);
+    }
+}
+
+#[test]
+fn panicking_clone_and_iterator() {
+    let items: MyVec<Bomb> = (0..4)
+        .map(|i| Bomb::make(if i == 2 { CLONE_BOMB } else { i }))
+        .collect();
+
+    let mut v = MyVec::new();
+    assert!(catch(|| v.extend_from_slice(&items)).is_err());
+    assert_eq!(v.len(), 2);
+    assert!(catch(|| v.extend_from_within(..)).is_ok());
+    assert_eq!(v.len(), 4);
+    assert!(catch(|| items.clone()).is_err());
+    assert!(catch(|| my_vec![Bomb::make(CLONE_BOMB); 3]).is_err());
+
+    let mut w = my_vec![Bomb::make(0), Bomb::make(CLONE_BOMB)];
+    assert!(catch(|| w.extend_from_within(..)).is_err());
+    assert_eq!(w.len(), 3);
+    assert!(catch(|| w.clone_from(&items)).is_err());
+    assert_eq!(w.len(), 3);
+
+    let mut nums: MyVec<u32> = MyVec::new();
+    let result = catch(|| nums.extend((0..10).inspect(|&i| assert!(i != 5, "iterator"))));
+    assert!(result.is_err());
+    assert_eq!(nums, [0, 1, 2, 3, 4]);
+
+    drop((v, w, items));
+    assert_alive(0);
+}